@@ -3,16 +3,16 @@ advent_of_code::solution!(1);
 pub fn part_one(input: &str) -> Option<usize> {
     let sum: usize = input
         .lines()
-        .map(|l| read_digits(l))
+        .map(read_digits)
         .map(|nums| get_number(&nums))
         .sum();
-    Some(sum as usize)
+    Some(sum)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let sum = input
         .lines()
-        .map(|l| read_digits_two(l))
+        .map(read_digits_two)
         .map(|nums| get_number(&nums))
         .sum();
     Some(sum)
@@ -45,7 +45,7 @@ pub fn read_digits_two(input: &str) -> Vec<usize> {
     digits
 }
 
-pub fn get_number(nums: &Vec<usize>) -> usize {
+pub fn get_number(nums: &[usize]) -> usize {
     let first = nums.first().unwrap();
     let last = nums.last().unwrap_or(first);
     first * 10 + last
@@ -88,7 +88,7 @@ zoneight234
 
     #[test]
     fn test_get_numbers() {
-        let result = get_number(&vec![1, 2, 0, 9, 2]);
+        let result = get_number(&[1, 2, 0, 9, 2]);
         assert_eq!(result, 12);
     }
 
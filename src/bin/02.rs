@@ -1,4 +1,6 @@
-use std::isize;
+use advent_of_code::parse::{key_value, lines, parse_all, unsigned, word, IResult};
+use nom::{character::complete::space1, multi::separated_list1, sequence::separated_pair, Parser};
+use nom_supreme::{tag::complete::tag, ParserExt};
 
 advent_of_code::solution!(2);
 
@@ -16,7 +18,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     let result = games
         .iter()
         .map(|g| g.min_cubes())
-        .map(|min_set| Game::power_set(min_set))
+        .map(Game::power_set)
         .sum();
     Some(result)
 }
@@ -24,30 +26,30 @@ pub fn part_two(input: &str) -> Option<usize> {
 #[derive(Debug, PartialEq)]
 struct Game(usize, Vec<(isize, isize, isize)>);
 impl Game {
-    fn new(input: &str) -> Game {
-        let (id_split, round_split) = input.split_once(':').unwrap();
+    fn parse(input: &str) -> IResult<'_, Game> {
+        let id = tag("Game ").precedes(unsigned);
+        let rounds = separated_list1(tag("; "), Game::parse_round);
+        key_value(id, rounds)
+            .map(|(id, rounds)| Game(id, rounds))
+            .parse(input)
+    }
 
-        let id = id_split[5..].parse::<usize>().unwrap();
-        let rounds = round_split
-            .split(';')
-            .map(|round| {
+    fn parse_round(input: &str) -> IResult<'_, (isize, isize, isize)> {
+        let color = word(&[("red", 'r'), ("green", 'g'), ("blue", 'b')]);
+        separated_list1(tag(", "), separated_pair(unsigned, space1, color))
+            .map(|cubes| {
                 let mut round_data = (0, 0, 0);
-                round.split(',').for_each(|item| {
-                    let parts: Vec<&str> = item.trim().split_whitespace().collect();
-                    let color = parts[1].chars().next().unwrap();
-                    let count = parts[0].parse::<isize>().unwrap();
+                for (count, color) in cubes {
                     match color {
                         'r' => round_data.0 = count,
                         'g' => round_data.1 = count,
                         'b' => round_data.2 = count,
-                        _ => panic!("Not possible"),
+                        _ => unreachable!(),
                     }
-                });
+                }
                 round_data
             })
-            .collect();
-
-        Game(id, rounds)
+            .parse(input)
     }
 
     fn valid(&self) -> bool {
@@ -71,7 +73,7 @@ impl Game {
 }
 
 fn parse_input(input: &str) -> Vec<Game> {
-    input.lines().map(|line| Game::new(line)).collect()
+    parse_all(lines(Game::parse), input).unwrap()
 }
 
 #[cfg(test)]
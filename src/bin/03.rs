@@ -1,15 +1,20 @@
+use advent_of_code::flag;
+use advent_of_code::template::{ANSI_BOLD, ANSI_RESET};
 use regex::Regex;
 use std::collections::HashSet;
 use std::ops::Range;
+use std::sync::OnceLock;
 
 advent_of_code::solution!(3);
 
 pub fn part_one(input: &str) -> Option<usize> {
     let schematic = parse_input(input);
+    if flag("--print") {
+        println!("{}", schematic.render());
+    }
     schematic
-        .iter()
-        .filter_map(|s| is_adj(s, &schematic))
-        .filter_map(|s| match s.value {
+        .part_numbers()
+        .filter_map(|(part, _)| match part.value {
             EngineSymbolValue::Number(n) => Some(n),
             _ => None,
         })
@@ -19,11 +24,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 
 pub fn part_two(input: &str) -> Option<usize> {
     let schematic = parse_input(input);
-    schematic
-        .iter()
-        .filter_map(|s| gear_ratio(s, &schematic))
-        .sum::<usize>()
-        .into()
+    schematic.gear_ratios().sum::<usize>().into()
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -46,8 +47,115 @@ enum EngineSymbolValue {
     Number(usize),
 }
 
-fn parse_input(input: &str) -> HashSet<EngineSymbol> {
-    input
+/// The engine schematic, with every cell pointing at the number or symbol covering it.
+#[derive(Debug)]
+struct Schematic {
+    items: Vec<EngineSymbol>,
+    cells: Vec<Vec<Option<usize>>>,
+}
+
+impl Schematic {
+    fn new(items: Vec<EngineSymbol>, width: usize, height: usize) -> Self {
+        let mut cells = vec![vec![None; width]; height];
+        for (id, item) in items.iter().enumerate() {
+            for x in item.position.clone() {
+                cells[item.line][x] = Some(id);
+            }
+        }
+        Schematic { items, cells }
+    }
+
+    /// Ids of everything in the cells surrounding an item, diagonals included.
+    fn neighbours(&self, id: usize) -> Vec<usize> {
+        let item = &self.items[id];
+        let rows = item.line.saturating_sub(1)..(item.line + 2).min(self.cells.len());
+        let mut neighbours: Vec<usize> = rows
+            .flat_map(|y| {
+                let row = &self.cells[y];
+                let columns =
+                    item.position.start.saturating_sub(1)..(item.position.end + 1).min(row.len());
+                row[columns].iter().flatten().copied()
+            })
+            .filter(|&other| other != id)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// Every number touching at least one symbol, along with the symbols it touches.
+    fn part_numbers(&self) -> impl Iterator<Item = (&EngineSymbol, Vec<&EngineSymbol>)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.symbol_type == EngineSymbolType::Number)
+            .map(|(id, item)| {
+                let symbols = self
+                    .neighbours(id)
+                    .into_iter()
+                    .map(|n| &self.items[n])
+                    .filter(|n| n.symbol_type == EngineSymbolType::Symbol)
+                    .collect::<Vec<_>>();
+                (item, symbols)
+            })
+            .filter(|(_, symbols)| !symbols.is_empty())
+    }
+
+    /// The product of the two numbers next to each gear, a `*` touching exactly two numbers.
+    fn gear_ratios(&self) -> impl Iterator<Item = usize> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.value == EngineSymbolValue::Symbol('*'))
+            .filter_map(|(id, _)| {
+                let numbers: Vec<usize> = self
+                    .neighbours(id)
+                    .into_iter()
+                    .filter_map(|n| match self.items[n].value {
+                        EngineSymbolValue::Number(n) => Some(n),
+                        _ => None,
+                    })
+                    .collect();
+                (numbers.len() == 2).then(|| numbers[0] * numbers[1])
+            })
+    }
+
+    /// The schematic as text, with part numbers and the symbols they touch in bold.
+    fn render(&self) -> String {
+        let highlighted: HashSet<&EngineSymbol> = self
+            .part_numbers()
+            .flat_map(|(part, symbols)| std::iter::once(part).chain(symbols))
+            .collect();
+
+        let mut output = String::new();
+        for row in &self.cells {
+            let mut x = 0;
+            while x < row.len() {
+                let Some(id) = row[x] else {
+                    output.push('.');
+                    x += 1;
+                    continue;
+                };
+                let item = &self.items[id];
+                let text = match item.value {
+                    EngineSymbolValue::Number(n) => n.to_string(),
+                    EngineSymbolValue::Symbol(c) => c.to_string(),
+                };
+                if highlighted.contains(item) {
+                    output.push_str(&format!("{ANSI_BOLD}{text}{ANSI_RESET}"));
+                } else {
+                    output.push_str(&text);
+                }
+                x = item.position.end;
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+fn parse_input(input: &str) -> Schematic {
+    let items = input
         .lines()
         .enumerate()
         .flat_map(|(i, line)| {
@@ -56,11 +164,14 @@ fn parse_input(input: &str) -> HashSet<EngineSymbol> {
                 s
             })
         })
-        .collect()
+        .collect();
+    let width = input.lines().map(|l| l.len()).max().unwrap_or(0);
+    Schematic::new(items, width, input.lines().count())
 }
 
 fn parse_line(line: &str) -> Vec<EngineSymbol> {
-    let re = Regex::new(r"(\d+)|([^\d.])").unwrap();
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"(\d+)|([^\d.])").unwrap());
     re.captures_iter(line)
         .filter_map(|cap| {
             cap.get(1)
@@ -84,54 +195,6 @@ fn parse_line(line: &str) -> Vec<EngineSymbol> {
         .collect()
 }
 
-fn is_adj(part: &EngineSymbol, schematic: &HashSet<EngineSymbol>) -> Option<EngineSymbol> {
-    if part.symbol_type != EngineSymbolType::Number {
-        return None;
-    }
-    let x_range = part.position.start.saturating_sub(1)..=part.position.end;
-    let y_range = part.line.saturating_sub(1)..=part.line + 1;
-
-    for y in y_range {
-        for x in x_range.clone() {
-            if schematic.iter().any(|s| {
-                s.symbol_type == EngineSymbolType::Symbol && s.line == y && s.position.contains(&x)
-            }) {
-                return Some(part.clone());
-            }
-        }
-    }
-    None
-}
-
-fn gear_ratio(part: &EngineSymbol, schematic: &HashSet<EngineSymbol>) -> Option<usize> {
-    if let EngineSymbolValue::Symbol('*') = part.value {
-        let x_range = part.position.start.saturating_sub(1)..=part.position.end;
-        let y_range = part.line.saturating_sub(1)..=part.line + 1;
-
-        let adjacent_numbers: Vec<usize> = schematic
-            .iter()
-            .filter(|s| s.symbol_type == EngineSymbolType::Number)
-            .filter(|s| {
-                y_range.contains(&s.line)
-                    && (x_range.contains(&s.position.start)
-                        || x_range.contains(&(s.position.end - 1)))
-            })
-            .filter_map(|s| {
-                if let EngineSymbolValue::Number(n) = s.value {
-                    Some(n)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if adjacent_numbers.len() == 2 {
-            return Some(adjacent_numbers[0] * adjacent_numbers[1]);
-        }
-    }
-    None
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +231,38 @@ mod tests {
         let result = parse_line(line);
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_neighbours() {
+        let schematic = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let parts: Vec<(usize, Vec<char>)> = schematic
+            .part_numbers()
+            .map(|(part, symbols)| match part.value {
+                EngineSymbolValue::Number(n) => (
+                    n,
+                    symbols
+                        .iter()
+                        .map(|s| match s.value {
+                            EngineSymbolValue::Symbol(c) => c,
+                            _ => unreachable!(),
+                        })
+                        .collect(),
+                ),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(parts.len(), 8);
+        assert_eq!(parts[0], (467, vec!['*']));
+        assert!(!parts.iter().any(|(n, _)| *n == 114 || *n == 58));
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "467..114..\n...*......\n";
+        let result = parse_input(input).render();
+        assert_eq!(
+            result,
+            format!("{ANSI_BOLD}467{ANSI_RESET}..114..\n...{ANSI_BOLD}*{ANSI_RESET}......\n")
+        );
+    }
 }
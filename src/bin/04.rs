@@ -1,3 +1,10 @@
+use advent_of_code::parse::{key_value, numbers, parse_all, unsigned, IResult};
+use nom::{
+    character::complete::{char, space1},
+    sequence::{separated_pair, tuple},
+    Parser,
+};
+use nom_supreme::{tag::complete::tag, ParserExt};
 use std::collections::HashSet;
 
 advent_of_code::solution!(4);
@@ -21,8 +28,9 @@ pub fn part_two(input: &str) -> Option<usize> {
         .fold(vec![1; games.len()], |mut copies, i| {
             let card = &games[i];
             let card_copies = copies[i];
-            for j in i + 1..std::cmp::min(games.len(), i + card.wins as usize + 1) {
-                copies[j] += card_copies;
+            let end = std::cmp::min(games.len(), i + card.wins + 1);
+            for copy in &mut copies[i + 1..end] {
+                *copy += card_copies;
             }
             copies
         })
@@ -49,9 +57,9 @@ impl Game {
     fn get_wins(&self) -> usize {
         self.player_numbers
             .iter()
-            .filter(|n| self.winning_numbers.contains(&n))
+            .filter(|n| self.winning_numbers.contains(n))
             .collect::<Vec<&usize>>()
-            .len() as usize
+            .len()
     }
 
     fn get_score(&self) -> usize {
@@ -65,39 +73,27 @@ impl Game {
 }
 
 fn parse_input(input: &str) -> Vec<Game> {
-    input.lines().map(|l| parse_line(l)).collect()
+    input.lines().map(parse_line).collect()
 }
 
 fn parse_line(line: &str) -> Game {
-    let number = line
-        .split(':')
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .nth(1)
-        .unwrap();
-    dbg!(&number);
-    let id = number.parse().unwrap();
-    let winning_numbers = line.split(&[':', '|']).nth(1).unwrap();
-    let player_numbers = line.rsplit('|').nth(0).unwrap();
-
-    let winning_numbers = winning_numbers
-        .split_whitespace()
-        .filter_map(|n| Some(n.parse::<usize>()))
-        .map(|n| n.unwrap())
-        .collect();
-
-    let player_numbers = player_numbers
-        .split_whitespace()
-        .filter_map(|n| Some(n.parse::<usize>()))
-        .map(|n| n.unwrap())
-        .collect();
+    parse_all(parse_card, line).unwrap()
+}
 
-    Game {
-        id,
-        winning_numbers,
-        player_numbers,
-    }
+fn parse_card(input: &str) -> IResult<'_, Game> {
+    let id = tag("Card").precedes(space1).precedes(unsigned);
+    let numbers = separated_pair(
+        numbers::<usize>,
+        tuple((space1, char('|'), space1)),
+        numbers::<usize>,
+    );
+    key_value(id, numbers)
+        .map(|(id, (winning_numbers, player_numbers))| Game {
+            id,
+            winning_numbers: winning_numbers.into_iter().collect(),
+            player_numbers: player_numbers.into_iter().collect(),
+        })
+        .parse(input)
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use rayon::prelude::*;
+use advent_of_code::interval::RangeSet;
 use std::ops::Range;
 
 advent_of_code::solution!(5);
@@ -10,9 +10,8 @@ struct Conversion {
 }
 impl Conversion {
     fn convert(&self, location: u64) -> Option<u64> {
-        let diff = location - self.source.start;
         if self.source.contains(&location) {
-            return Some(self.destination.start + diff);
+            return Some(self.destination.start + (location - self.source.start));
         }
         None
     }
@@ -33,6 +32,17 @@ impl ConversionChart {
         }
         location
     }
+
+    /// Converts every value in `ranges` at once, splitting them wherever a conversion starts or
+    /// ends.
+    fn convert_ranges(&self, ranges: &RangeSet<u64>) -> RangeSet<u64> {
+        let mappings: Vec<(Range<u64>, u64)> = self
+            .conversions
+            .iter()
+            .map(|c| (c.source.clone(), c.destination.start))
+            .collect();
+        ranges.remap(&mappings)
+    }
 }
 
 #[derive(Debug)]
@@ -53,7 +63,6 @@ impl Almanac {
             .map(|s| self.seed_to_location(*s))
             .min()
             .unwrap()
-        // .collect::<Vec<u64>>()
     }
 
     fn seed_to_location(&self, seed: u64) -> u64 {
@@ -63,15 +72,10 @@ impl Almanac {
     }
 
     fn range_locations(&self) -> u64 {
-        self.seeds_to_ranges()
-            .par_iter()
-            .map(|r| {
-                dbg!(r);
-                r.clone()
-                    .map(|s| self.seed_to_location(s))
-                    .min()
-                    .unwrap_or(100)
-            })
+        let seeds: RangeSet<u64> = self.seeds_to_ranges().into_iter().collect();
+        self.charts
+            .iter()
+            .fold(seeds, |ranges, chart| chart.convert_ranges(&ranges))
             .min()
             .unwrap()
     }
@@ -107,7 +111,7 @@ fn parse_input(input: &str) -> Almanac {
                     conversions: Vec::new(),
                 });
             }
-            l if l.is_empty() => {}
+            "" => {}
             l => {
                 let numbers: Vec<u64> = l
                     .trim()
@@ -144,4 +148,12 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(46));
     }
+
+    #[test]
+    fn test_convert_ranges() {
+        let almanac = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let soil = almanac.charts[0].convert_ranges(&RangeSet::from(97..101));
+        assert_eq!(soil.ranges(), &[50..52, 99..101]);
+        assert_eq!(almanac.charts[0].convert(10), 10);
+    }
 }
@@ -1,3 +1,6 @@
+use advent_of_code::math::below_zero;
+use std::ops::RangeInclusive;
+
 advent_of_code::solution!(6);
 
 #[derive(Debug)]
@@ -6,17 +9,16 @@ struct Race {
     distance: u64,
 }
 impl Race {
-    fn does_win(&self, held_down: u64) -> bool {
-        let speed = held_down;
-        let time_remaining = self.time - held_down;
-        self.distance < time_remaining * speed
+    /// Holding the button for `h` wins when `h * (time - h) > distance`, or equivalently
+    /// `h² - time * h + distance < 0`.
+    fn winning_holds(&self) -> Option<RangeInclusive<u64>> {
+        below_zero(1, -(self.time as i128), self.distance as i128)
+            .map(|held| *held.start() as u64..=*held.end() as u64)
     }
 
     fn ways_to_win(&self) -> u64 {
-        (0..self.time)
-            .filter(|t| self.does_win(*t))
-            .map(|_| 1)
-            .sum()
+        self.winning_holds()
+            .map_or(0, |held| held.end() - held.start() + 1)
     }
 }
 
@@ -94,15 +96,30 @@ mod tests {
     }
 
     #[test]
-    fn test_does_win() {
+    fn test_winning_holds() {
+        let race = Race {
+            time: 30,
+            distance: 200,
+        };
+        let held = race.winning_holds().unwrap();
+        assert!(!held.contains(&10));
+        assert!(held.contains(&11));
+        assert!(held.contains(&19));
+        assert!(!held.contains(&20));
+    }
+
+    #[test]
+    fn test_ways_to_win() {
         let race = Race {
             time: 30,
             distance: 200,
         };
-        assert_eq!(race.does_win(10), false);
-        assert_eq!(race.does_win(11), true);
-        assert_eq!(race.does_win(19), true);
-        assert_eq!(race.does_win(20), false);
+        assert_eq!(race.ways_to_win(), 9);
+        let race = Race {
+            time: 4,
+            distance: 4,
+        };
+        assert_eq!(race.ways_to_win(), 0);
     }
 
     #[test]
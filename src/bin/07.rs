@@ -1,54 +1,178 @@
 advent_of_code::solution!(7);
+use advent_of_code::parse::{key_value, parse_all, word, IResult};
 use itertools::Itertools;
-use std::{cmp::Ordering, collections::HashMap};
+use nom::{
+    character::complete::{line_ending, none_of, space1},
+    multi::{many0, separated_list1},
+    sequence::{terminated, tuple},
+    Parser,
+};
+use nom_supreme::{error::ErrorTree, final_parser::Location, tag::complete::tag, ParserExt};
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+/// The rules of the first part, as a ruleset config.
+const STANDARD: &str = "order: 23456789TJQKA
+wildcards:
+categories: high-card one-pair two-pair three-of-a-kind full-house four-of-a-kind five-of-a-kind";
+
+/// The rules of the second part, where jokers are wild but the weakest card on their own.
+const JOKERS: &str = "order: J23456789TQKA
+wildcards: J
+categories: high-card one-pair two-pair three-of-a-kind full-house four-of-a-kind five-of-a-kind";
 
 #[derive(Debug, PartialEq, Hash, Clone, Eq, Copy)]
 struct Card {
     value: char,
 }
-impl Card {
-    fn to_rank(&self) -> usize {
-        match self.value {
-            'A' => 14,
-            'K' => 13,
-            'Q' => 12,
-            'J' => 11,
-            'T' => 10,
-            '9' => 9,
-            '8' => 8,
-            '7' => 7,
-            '6' => 6,
-            '5' => 5,
-            '4' => 4,
-            '3' => 3,
-            '2' => 2,
-            '*' => 0,
-            _ => 0,
-        }
-    }
-}
 
-#[derive(Debug, PartialEq, Hash, Eq, Ord, PartialOrd)]
+/// Hand categories, weakest first.
+#[derive(Debug, PartialEq, Hash, Eq, Ord, PartialOrd, Clone, Copy)]
 enum HandRanks {
-    FiveOfAKind,
-    FourOfAKind,
-    FullHouse,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
     HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
 }
 impl HandRanks {
-    fn to_rank(&self) -> usize {
+    /// Whether a hand with these card counts, largest first, makes this category.
+    fn fits(&self, counts: &[usize]) -> bool {
         match self {
-            HandRanks::FiveOfAKind => 6,
-            HandRanks::FourOfAKind => 5,
-            HandRanks::FullHouse => 4,
-            HandRanks::ThreeOfAKind => 3,
-            HandRanks::TwoPair => 2,
-            HandRanks::OnePair => 1,
-            HandRanks::HighCard => 0,
+            HandRanks::FiveOfAKind => counts[0] >= 5,
+            HandRanks::FourOfAKind => counts[0] >= 4,
+            HandRanks::FullHouse => counts[0] >= 3 && counts[1] >= 2,
+            HandRanks::ThreeOfAKind => counts[0] >= 3,
+            HandRanks::TwoPair => counts[0] >= 2 && counts[1] >= 2,
+            HandRanks::OnePair => counts[0] >= 2,
+            HandRanks::HighCard => true,
+        }
+    }
+}
+
+/// How hands are ranked against each other.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Ruleset {
+    /// Every card, weakest first. Ties between hands of the same category are broken by this.
+    order: Vec<char>,
+    /// Cards that stand in for whichever card makes the hand strongest.
+    wildcards: Vec<char>,
+    /// The categories in play, weakest first. A hand falls back to the strongest of them it makes.
+    categories: Vec<HandRanks>,
+}
+impl Ruleset {
+    fn standard() -> Ruleset {
+        Ruleset::from_config(STANDARD).unwrap()
+    }
+
+    fn jokers() -> Ruleset {
+        Ruleset::from_config(JOKERS).unwrap()
+    }
+
+    /// Reads a ruleset from lines like `order: 23456789TJQKA`, `wildcards: J` and
+    /// `categories: high-card one-pair`, in that order.
+    fn from_config(config: &str) -> Result<Ruleset, RulesetError> {
+        let mut ruleset = parse_all(parse_ruleset, config)?;
+        ruleset.categories.sort_unstable();
+        ruleset.categories.dedup();
+
+        if ruleset.order.len() > 16 {
+            return Err(RulesetError::TooManyCards(ruleset.order.len()));
         }
+        if let Some((_, &card)) = ruleset
+            .order
+            .iter()
+            .enumerate()
+            .find(|&(i, card)| ruleset.order[..i].contains(card))
+        {
+            return Err(RulesetError::DuplicateCard(card));
+        }
+        if let Some(&card) = ruleset
+            .wildcards
+            .iter()
+            .find(|card| !ruleset.order.contains(card))
+        {
+            return Err(RulesetError::UnrankedWildcard(card));
+        }
+        Ok(ruleset)
+    }
+
+    fn category(&self, hand: &Hand) -> Option<HandRanks> {
+        let mut counts = hand.get_counts();
+        let wild: usize = self
+            .wildcards
+            .iter()
+            .filter_map(|&value| counts.remove(&Card { value }))
+            .sum();
+
+        let mut counts = counts.into_values().collect::<Vec<usize>>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts.resize(counts.len().max(2), 0);
+        counts[0] += wild;
+
+        self.categories
+            .iter()
+            .rev()
+            .find(|category| category.fits(&counts))
+            .copied()
+    }
+
+    /// Position of the card in the order. `from_config` has already checked the ruleset's own
+    /// cards, so this only panics on a hand holding a card the ruleset doesn't know.
+    fn card_rank(&self, card: &Card) -> u64 {
+        self.order
+            .iter()
+            .position(|&c| c == card.value)
+            .unwrap_or_else(|| panic!("{} is not in the ruleset", card.value)) as u64
+    }
+
+    /// A single number that sorts hands weakest first: the category followed by 4 bits per card.
+    fn key(&self, hand: &Hand) -> u64 {
+        let category = self
+            .category(hand)
+            .map_or(0, |c| self.categories.binary_search(&c).unwrap() as u64 + 1);
+        hand.cards
+            .iter()
+            .fold(category, |key, card| key << 4 | self.card_rank(card))
+    }
+
+    fn total_winnings(&self, mut hands: Vec<Hand>) -> usize {
+        hands.sort_by_cached_key(|hand| self.key(hand));
+        hands
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (rank, hand)| acc + hand.bid * (rank + 1))
+    }
+}
+
+/// An error which can be returned when a ruleset config can't be used.
+#[derive(Debug)]
+enum RulesetError {
+    Parse(ErrorTree<Location>),
+    /// Card ranks are packed 4 bits each, so there can be at most 16 of them.
+    TooManyCards(usize),
+    DuplicateCard(char),
+    /// Wildcards still need a rank of their own for breaking ties.
+    UnrankedWildcard(char),
+}
+
+impl Error for RulesetError {}
+
+impl Display for RulesetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesetError::Parse(e) => write!(f, "invalid ruleset config: {e}"),
+            RulesetError::TooManyCards(n) => write!(f, "{n} cards in the order, at most 16 fit"),
+            RulesetError::DuplicateCard(c) => write!(f, "{c} is in the order more than once"),
+            RulesetError::UnrankedWildcard(c) => write!(f, "wildcard {c} is not in the order"),
+        }
+    }
+}
+
+impl From<ErrorTree<Location>> for RulesetError {
+    fn from(e: ErrorTree<Location>) -> Self {
+        RulesetError::Parse(e)
     }
 }
 
@@ -69,118 +193,47 @@ impl Hand {
         let bid: usize = parts.next().unwrap().parse().unwrap();
         Hand { cards, bid }
     }
-    fn from_string_jokers(s: &str) -> Hand {
-        let mut parts = s.split_whitespace();
-        let cards: Vec<Card> = parts
-            .next()
-            .unwrap()
-            .chars()
-            .map(|c| {
-                if c == 'J' {
-                    return Card { value: '*' };
-                }
-                Card { value: c }
-            })
-            .collect();
-        let bid: usize = parts.next().unwrap().parse().unwrap();
-        Hand { cards, bid }
-    }
     fn get_counts(&self) -> HashMap<Card, usize> {
         self.cards.clone().into_iter().counts()
     }
-
-    fn get_rank(&self) -> HandRanks {
-        let mut counts = self.get_counts();
-        let jokers = counts.remove(&Card { value: '*' }).unwrap_or(0);
-        if jokers == 5 {
-            return HandRanks::FiveOfAKind;
-        };
-
-        let mut counts = counts.into_values().collect::<Vec<usize>>();
-        counts.sort_unstable_by(|a, b| b.cmp(a));
-        counts[0] += jokers;
-
-        match counts[0] {
-            5..=10 => HandRanks::FiveOfAKind,
-            4 => HandRanks::FourOfAKind,
-            3 => {
-                if counts[1] == 2 {
-                    HandRanks::FullHouse
-                } else {
-                    HandRanks::ThreeOfAKind
-                }
-            }
-            2 => {
-                if counts[1] == 2 {
-                    HandRanks::TwoPair
-                } else {
-                    HandRanks::OnePair
-                }
-            }
-            1 => HandRanks::HighCard,
-            _ => unreachable!(),
-        }
-    }
-
-    fn to_rank(&self) -> usize {
-        self.get_rank().to_rank()
-    }
-
-    fn card_rank(&self, idx: usize) -> usize {
-        self.cards[idx].to_rank()
-    }
-}
-
-impl PartialOrd<Self> for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.cards == other.cards {
-            return Ordering::Equal;
-        }
-        let mut candidate = self.to_rank().cmp(&other.to_rank());
-        let mut i = 0;
-        while candidate == Ordering::Equal {
-            candidate = self.card_rank(i).cmp(&other.card_rank(i));
-            i += 1;
-        }
-        candidate
-    }
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let mut hands = parse_input(input);
-    hands.sort();
-    hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (rank, hand)| acc + hand.bid * (rank as usize + 1))
-        .into()
+    Some(Ruleset::standard().total_winnings(parse_input(input)))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let mut hands = parse_input_two(input);
-    // for hand in &hands{
-    //     assert_eq!(&0, hand.get_counts().get(&Card{value: 'J'}).unwrap_or(&0));
-    // }
-    hands.sort();
-    hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (rank, hand)| acc + hand.bid * (rank as usize + 1))
-        .into()
+    Some(Ruleset::jokers().total_winnings(parse_input(input)))
 }
 
 fn parse_input(input: &str) -> Vec<Hand> {
-    input.lines().map(|l| Hand::from_string(l)).collect()
+    input.lines().map(Hand::from_string).collect()
 }
 
-fn parse_input_two(input: &str) -> Vec<Hand> {
-    input.lines().map(|l| Hand::from_string_jokers(l)).collect()
+fn parse_ruleset(input: &str) -> IResult<'_, Ruleset> {
+    let cards = || many0(none_of(" \r\n"));
+    let category = word(&[
+        ("high-card", HandRanks::HighCard),
+        ("one-pair", HandRanks::OnePair),
+        ("two-pair", HandRanks::TwoPair),
+        ("three-of-a-kind", HandRanks::ThreeOfAKind),
+        ("full-house", HandRanks::FullHouse),
+        ("four-of-a-kind", HandRanks::FourOfAKind),
+        ("five-of-a-kind", HandRanks::FiveOfAKind),
+    ]);
+
+    tuple((
+        terminated(key_value(tag("order"), cards()), line_ending),
+        terminated(key_value(tag("wildcards"), cards()), line_ending),
+        key_value(tag("categories"), separated_list1(space1, category)),
+    ))
+    .map(|((_, order), (_, wildcards), (_, categories))| Ruleset {
+        order,
+        wildcards,
+        categories,
+    })
+    .context("ruleset")
+    .parse(input)
 }
 
 #[cfg(test)]
@@ -241,24 +294,77 @@ mod tests {
 
     #[test]
     fn test_hand_get_rank() {
-        let hand = Hand::from_string("32T3K 765");
-        assert_eq!(hand.get_rank(), HandRanks::OnePair);
-        let hand = Hand::from_string("T55J5 684");
-        assert_eq!(hand.get_rank(), HandRanks::ThreeOfAKind);
-        let hand = Hand::from_string("KK677 430");
-        assert_eq!(hand.get_rank(), HandRanks::TwoPair);
-        let hand = Hand::from_string("KTJ32 304");
-        assert_eq!(hand.get_rank(), HandRanks::HighCard);
-        let hand = Hand::from_string("QQQKK 400");
-        assert_eq!(hand.get_rank(), HandRanks::FullHouse);
+        let rules = Ruleset::standard();
+        let category = |s| rules.category(&Hand::from_string(s));
+        assert_eq!(category("32T3K 765"), Some(HandRanks::OnePair));
+        assert_eq!(category("T55J5 684"), Some(HandRanks::ThreeOfAKind));
+        assert_eq!(category("KK677 430"), Some(HandRanks::TwoPair));
+        assert_eq!(category("KTJ32 304"), Some(HandRanks::HighCard));
+        assert_eq!(category("QQQKK 400"), Some(HandRanks::FullHouse));
+
+        let rules = Ruleset::jokers();
+        let category = |s| rules.category(&Hand::from_string(s));
+        assert_eq!(category("T55J5 684"), Some(HandRanks::FourOfAKind));
+        assert_eq!(category("JJJJJ 1"), Some(HandRanks::FiveOfAKind));
     }
 
     #[test]
     fn test_hand_cmp() {
+        let rules = Ruleset::standard();
         let hand_one = Hand::from_string("32T3K 765");
         let hand_two = Hand::from_string("T55J5 684");
-        assert_eq!(hand_one.to_rank(), 1);
-        assert_eq!(hand_two.to_rank(), 3);
-        assert!(hand_two > hand_one);
+        assert_eq!(rules.category(&hand_one), Some(HandRanks::OnePair));
+        assert_eq!(rules.category(&hand_two), Some(HandRanks::ThreeOfAKind));
+        assert!(rules.key(&hand_two) > rules.key(&hand_one));
+
+        // Same category, so the first differing card decides
+        let hand_one = Hand::from_string("KK677 28");
+        let hand_two = Hand::from_string("KTJJT 220");
+        assert!(rules.key(&hand_one) > rules.key(&hand_two));
+        // Unless jokers make the second one stronger
+        assert!(Ruleset::jokers().key(&hand_two) > Ruleset::jokers().key(&hand_one));
+    }
+
+    #[test]
+    fn test_ruleset_config() {
+        assert_eq!(Ruleset::jokers().wildcards, vec!['J']);
+        assert!(Ruleset::standard().wildcards.is_empty());
+
+        // Without full houses or two pairs, they only count as three of a kind and one pair
+        let rules = Ruleset::from_config(
+            "order: 23456789TJQKA
+wildcards:
+categories: one-pair three-of-a-kind high-card",
+        )
+        .unwrap();
+        let category = |s| rules.category(&Hand::from_string(s));
+        assert_eq!(category("QQQKK 1"), Some(HandRanks::ThreeOfAKind));
+        assert_eq!(category("KK677 1"), Some(HandRanks::OnePair));
+        assert_eq!(category("AAAAA 1"), Some(HandRanks::ThreeOfAKind));
+
+        assert!(matches!(
+            Ruleset::from_config("order: 23\ncategories: high-card"),
+            Err(RulesetError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_ruleset_config_errors() {
+        let config = |order, wildcards| {
+            Ruleset::from_config(&format!(
+                "order: {order}\nwildcards: {wildcards}\ncategories: high-card"
+            ))
+        };
+        assert!(matches!(
+            config("0123456789ABCDEFG", ""),
+            Err(RulesetError::TooManyCards(17))
+        ));
+        assert!(matches!(
+            config("23456789TJQKAK", ""),
+            Err(RulesetError::DuplicateCard('K'))
+        ));
+        let error = config("23456789TQKA", "J").unwrap_err();
+        assert_eq!(error.to_string(), "wildcard J is not in the order");
+        assert!(config("0123456789ABCDEF", "0").is_ok());
     }
 }
@@ -1,63 +1,143 @@
-use num::integer::lcm;
+use advent_of_code::{cycle, math::crt};
+use itertools::Itertools;
 use std::collections::HashMap;
 
 advent_of_code::solution!(8);
 
 #[derive(Debug)]
 struct Node {
-    left: String,
-    right: String,
+    left: usize,
+    right: usize,
+}
+
+/// The desert map with every node name swapped for its index into `nodes`.
+#[derive(Debug)]
+struct Network {
+    instructions: Vec<char>,
+    nodes: Vec<Node>,
+    names: Vec<String>,
+}
+
+/// Where a ghost is: the node, and how far through the instructions it is.
+type State = (usize, usize);
+
+/// Every step a ghost is on a Z node, in the form of the hits before it starts looping and the
+/// hits during the first time round the loop.
+#[derive(Debug, PartialEq, Eq)]
+struct GhostCycle {
+    prefix: u64,
+    length: u64,
+    prefix_hits: Vec<u64>,
+    cycle_hits: Vec<u64>,
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let (instructions, map) = parse_input(input);
+    let network = parse_input(input);
+    let start = network.node("AAA")?;
+    let end = network.node("ZZZ")?;
     let mut steps = 0;
-    let mut location = "AAA".to_string();
+    let mut state = (start, 0);
 
-    while location != "ZZZ" {
-        let direction = instructions[steps as usize % instructions.len()];
-        location = match direction {
-            'L' => map[&location].left.clone(),
-            'R' => map[&location].right.clone(),
-            _ => unreachable!(),
-        };
+    while state.0 != end {
+        state = network.step(&state);
         steps += 1;
     }
     Some(steps)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let (instructions, map) = parse_input(input);
-    let start_locations: Vec<String> = map.keys().filter(|&s| s.ends_with("A")).cloned().collect();
-
-    let cycle_lengths: Vec<u64> = start_locations
-        .iter()
-        .map(|start| find_cycle_length(start, &instructions, &map))
+    let network = parse_input(input);
+    let cycles: Vec<GhostCycle> = (0..network.nodes.len())
+        .filter(|&n| network.names[n].ends_with('A'))
+        .map(|start| network.ghost_cycle(start))
         .collect();
-
-    Some(cycle_lengths.into_iter().fold(1, lcm))
+    earliest_common_hit(&cycles)
 }
 
-fn find_cycle_length(start: &str, instructions: &Vec<char>, map: &HashMap<String, Node>) -> u64 {
-    let mut steps: u64 = 0;
-    let mut location = start;
-    while !location.ends_with("Z") {
-        let direction = instructions[steps as usize % instructions.len()];
-        location = match direction {
-            'L' => &map[location].left,
-            'R' => &map[location].right,
+impl Network {
+    fn node(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    fn step(&self, &(node, instruction): &State) -> State {
+        let next = match self.instructions[instruction] {
+            'L' => self.nodes[node].left,
+            'R' => self.nodes[node].right,
             _ => unreachable!(),
         };
-        steps += 1
+        (next, (instruction + 1) % self.instructions.len())
+    }
+
+    /// Follows a ghost until its state repeats, noting down every time it lands on a Z node.
+    fn ghost_cycle(&self, start: usize) -> GhostCycle {
+        let initial = (start, 0);
+        let found = cycle::brent(initial, |s| self.step(s), 0);
+        let (prefix, length) = (found.prefix as u64, found.length as u64);
+
+        let mut state = initial;
+        let mut hits = vec![];
+        for steps in 0..prefix + length {
+            if self.names[state.0].ends_with('Z') {
+                hits.push(steps);
+            }
+            state = self.step(&state);
+        }
+        let (prefix_hits, cycle_hits) = hits.into_iter().partition(|&h| h < prefix);
+
+        GhostCycle {
+            prefix,
+            length,
+            prefix_hits,
+            cycle_hits,
+        }
+    }
+}
+
+impl GhostCycle {
+    fn is_hit(&self, steps: u64) -> bool {
+        if steps < self.prefix {
+            self.prefix_hits.contains(&steps)
+        } else {
+            let lapped = self.prefix + (steps - self.prefix) % self.length;
+            self.cycle_hits.contains(&lapped)
+        }
+    }
+}
+
+/// The first step at which every ghost is on a Z node at once, if that ever happens.
+fn earliest_common_hit(cycles: &[GhostCycle]) -> Option<u64> {
+    // Before the slowest ghost starts looping it can only be one of that ghost's early hits
+    let slowest = cycles.iter().max_by_key(|c| c.prefix)?;
+    if let Some(&steps) = slowest
+        .prefix_hits
+        .iter()
+        .find(|&&steps| cycles.iter().all(|c| c.is_hit(steps)))
+    {
+        return Some(steps);
     }
-    steps
+
+    // After that every ghost is looping, so try each combination of hits within the loops
+    cycles
+        .iter()
+        .map(|c| {
+            c.cycle_hits
+                .iter()
+                .map(move |&hit| (hit as i128, c.length as i128))
+        })
+        .multi_cartesian_product()
+        .filter_map(|congruences| {
+            let (steps, period) = crt(&congruences)?;
+            let rounds = (slowest.prefix as i128 - steps + period - 1).max(0) / period;
+            Some((steps + rounds * period) as u64)
+        })
+        .min()
 }
 
-fn parse_input(input: &str) -> (Vec<char>, HashMap<String, Node>) {
+fn parse_input(input: &str) -> Network {
     let mut lines = input.lines();
     let instructions = lines.next().unwrap().chars().collect();
     lines.next();
-    let node_map = lines
+    let connections: Vec<Vec<&str>> = lines
         .map(|l| {
             l.split(&['=', '(', ',', ')'])
                 .map(|x| x.trim())
@@ -65,17 +145,27 @@ fn parse_input(input: &str) -> (Vec<char>, HashMap<String, Node>) {
                 .collect::<Vec<&str>>()
         })
         .filter(|l| !l.is_empty())
-        .map(|l| {
-            (
-                l[0].to_string(),
-                Node {
-                    left: l[1].to_string(),
-                    right: l[2].to_string(),
-                },
-            )
+        .collect();
+
+    let index: HashMap<&str, usize> = connections
+        .iter()
+        .enumerate()
+        .map(|(i, l)| (l[0], i))
+        .collect();
+    let nodes = connections
+        .iter()
+        .map(|l| Node {
+            left: index[l[1]],
+            right: index[l[2]],
         })
         .collect();
-    (instructions, node_map)
+    let names = connections.iter().map(|l| l[0].to_string()).collect();
+
+    Network {
+        instructions,
+        nodes,
+        names,
+    }
 }
 
 #[cfg(test)]
@@ -105,12 +195,71 @@ XXX = (XXX, XXX)",
         assert_eq!(result, Some(6));
     }
 
+    #[test]
+    fn test_part_two_multiple_hits() {
+        // The first ghost is on a Z at steps 2, 3, 7, 8, 12, 13, ... and the second at 4, 8, 12, ...
+        let result = part_two(
+            "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (12Z, 12Z)
+12Z = (11C, 11C)
+11C = (11D, 11D)
+11D = (11B, 11B)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22D, 22D)
+22D = (22Z, 22Z)
+22Z = (22B, 22B)",
+        );
+        assert_eq!(result, Some(8));
+    }
+
+    #[test]
+    fn test_part_two_before_looping() {
+        let result = part_two(
+            "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22Z, 22Z)",
+        );
+        assert_eq!(result, Some(1));
+    }
+
+    #[test]
+    fn test_ghost_cycle() {
+        let network = parse_input(
+            "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11B, 11B)",
+        );
+        let result = network.ghost_cycle(network.node("11A").unwrap());
+        assert_eq!(
+            result,
+            GhostCycle {
+                prefix: 1,
+                length: 3,
+                prefix_hits: vec![],
+                cycle_hits: vec![2],
+            }
+        );
+        assert!(result.is_hit(5));
+        assert!(!result.is_hit(6));
+    }
+
     #[test]
     fn test_parse_input() {
         let input = &advent_of_code::template::read_file("examples", DAY);
         let result = parse_input(input);
-        assert_eq!(result.0, vec!['R', 'L']);
-        dbg!(&result.1);
-        assert_eq!(result.1.len(), 7);
+        assert_eq!(result.instructions, vec!['R', 'L']);
+        assert_eq!(result.nodes.len(), 7);
+        assert_eq!(result.nodes[0].left, result.node("BBB").unwrap());
     }
 }
@@ -1,3 +1,6 @@
+use advent_of_code::Direction;
+use std::collections::HashSet;
+
 advent_of_code::solution!(10);
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -24,12 +27,90 @@ impl Sketch {
         Point { x: 0, y: 0 }
     }
 
-    fn follow_path(&self) -> Vec<(usize, usize)> {
-        let mut path = vec![];
-        let current = self.find_start();
+    fn pipe(&self, point: &Point) -> PipeType {
+        self.grid[point.y][point.x]
+    }
+
+    /// Works out which pipe is hiding under the `S`, from the neighbours that connect back to it.
+    fn start_pipe(&self) -> PipeType {
+        let start = self.find_start();
+        let connected: Vec<Direction> = PipeType::Start
+            .connections()
+            .iter()
+            .filter(|&&direction| {
+                step(&start, direction, self.width, self.height).is_some_and(|next| {
+                    self.pipe(&next)
+                        .connections()
+                        .contains(&direction.opposite())
+                })
+            })
+            .copied()
+            .collect();
+
+        PipeType::from_connections(&connected).expect("start should connect to two pipes")
+    }
+
+    fn follow_path(&self) -> Vec<Point> {
+        let start = self.find_start();
+        let start_pipe = self.start_pipe();
+        let mut previous = start.clone();
+        let mut current = start_pipe.neighbor(&start, self.width, self.height)[0].clone();
+        let mut path = vec![start.clone()];
 
+        while current != start {
+            path.push(current.clone());
+            // Leave through whichever end of the pipe we didn't come in from
+            let next = self
+                .pipe(&current)
+                .neighbor(&current, self.width, self.height)
+                .into_iter()
+                .find(|p| *p != previous)
+                .expect("loop should not be broken");
+            previous = current;
+            current = next;
+        }
         path
     }
+
+    /// Counts tiles inside the loop, scanning each row and flipping between outside and inside
+    /// every time a loop pipe with a northward connection is crossed.
+    fn enclosed_tiles(&self) -> usize {
+        let path: HashSet<Point> = self.follow_path().into_iter().collect();
+        let start_pipe = self.start_pipe();
+
+        (0..self.height)
+            .map(|y| {
+                let mut inside = false;
+                let mut count = 0;
+                for x in 0..self.width {
+                    let point = Point { x, y };
+                    if path.contains(&point) {
+                        let pipe = match self.pipe(&point) {
+                            PipeType::Start => start_pipe,
+                            pipe => pipe,
+                        };
+                        if pipe.connections().contains(&Direction::Up) {
+                            inside = !inside;
+                        }
+                    } else if inside {
+                        count += 1;
+                    }
+                }
+                count
+            })
+            .sum()
+    }
+}
+
+/// The neighbouring point in `direction`, if it's still on the grid.
+fn step(point: &Point, direction: Direction, width: usize, height: usize) -> Option<Point> {
+    let Point { x, y } = *point;
+    match direction {
+        Direction::Up => y.checked_sub(1).map(|y| Point { x, y }),
+        Direction::Down => (y + 1 < height).then_some(Point { x, y: y + 1 }),
+        Direction::Left => x.checked_sub(1).map(|x| Point { x, y }),
+        Direction::Right => (x + 1 < width).then_some(Point { x: x + 1, y }),
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -57,36 +138,58 @@ impl PipeType {
         }
     }
 
-    fn neighbor(self, Point { x, y }: Point) -> Vec<Point> {
+    /// The pipe that connects exactly the two given directions.
+    fn from_connections(connections: &[Direction]) -> Option<PipeType> {
+        [
+            PipeType::NorthSouth,
+            PipeType::EastWest,
+            PipeType::NorthEast,
+            PipeType::NorthWest,
+            PipeType::SouthWest,
+            PipeType::SouthEast,
+        ]
+        .into_iter()
+        .find(|pipe| {
+            connections.len() == 2 && connections.iter().all(|d| pipe.connections().contains(d))
+        })
+    }
+
+    fn connections(self) -> &'static [Direction] {
         match self {
-            PipeType::NorthSouth => vec![Point { x, y: y + 1 }, Point { x, y: y - 1 }],
-            PipeType::NorthEast => vec![Point { x, y: y + 1 }, Point { x: x + 1, y }],
-            PipeType::NorthWest => vec![Point { x, y: y + 1 }, Point { x: x - 1, y }],
-            PipeType::SouthEast => vec![Point { x, y: y - 1 }, Point { x: x + 1, y }],
-            PipeType::SouthWest => vec![Point { x, y: y - 1 }, Point { x: x - 1, y }],
-            PipeType::EastWest => vec![Point { x: x + 1, y }, Point { x: x + 1, y }],
-            PipeType::Start => vec![
-                Point { x, y: y + 1 },
-                Point { x, y: y - 1 },
-                Point { x: x + 1, y },
-                Point { x: x + 1, y },
+            PipeType::NorthSouth => &[Direction::Up, Direction::Down],
+            PipeType::EastWest => &[Direction::Right, Direction::Left],
+            PipeType::NorthEast => &[Direction::Up, Direction::Right],
+            PipeType::NorthWest => &[Direction::Up, Direction::Left],
+            PipeType::SouthWest => &[Direction::Down, Direction::Left],
+            PipeType::SouthEast => &[Direction::Down, Direction::Right],
+            PipeType::Start => &[
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
             ],
-            PipeType::None => vec![],
+            PipeType::None => &[],
         }
     }
+
+    /// Every point this pipe leads to that's still on the grid.
+    fn neighbor(self, point: &Point, width: usize, height: usize) -> Vec<Point> {
+        self.connections()
+            .iter()
+            .filter_map(|&direction| step(point, direction, width, height))
+            .collect()
+    }
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
     let sketch = parse_input(input);
-    // dbg!(&sketch);
     let path = sketch.follow_path();
-    dbg!(&path);
-    Some(path.len() as usize / 2);
-    None
+    Some(path.len() / 2)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    None
+    let sketch = parse_input(input);
+    Some(sketch.enclosed_tiles())
 }
 
 fn parse_input(input: &str) -> Sketch {
@@ -94,7 +197,7 @@ fn parse_input(input: &str) -> Sketch {
         .lines()
         .map(|line| {
             line.chars()
-                .map(|c| PipeType::from_char(c))
+                .map(PipeType::from_char)
                 .collect::<Vec<PipeType>>()
         })
         .collect::<Vec<Vec<PipeType>>>();
@@ -113,13 +216,62 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(8));
+        let input = "-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF";
+        assert_eq!(part_one(input), Some(4));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let input = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+        assert_eq!(part_two(input), Some(4));
+
+        let input = "..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........";
+        assert_eq!(part_two(input), Some(4));
+
+        let input = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...";
+        assert_eq!(part_two(input), Some(8));
+
+        let input = "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L";
+        assert_eq!(part_two(input), Some(10));
     }
 
     #[test]
@@ -134,4 +286,20 @@ mod tests {
         let result = sketch.find_start();
         assert_eq!(result, Point { x: 0, y: 2 });
     }
+
+    #[test]
+    fn test_start_pipe() {
+        let sketch = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(sketch.start_pipe(), PipeType::SouthEast);
+    }
+
+    #[test]
+    fn test_neighbor() {
+        let corner = Point { x: 0, y: 0 };
+        assert_eq!(PipeType::NorthWest.neighbor(&corner, 5, 5), vec![]);
+        assert_eq!(
+            PipeType::EastWest.neighbor(&Point { x: 1, y: 1 }, 5, 5),
+            vec![Point { x: 2, y: 1 }, Point { x: 0, y: 1 }]
+        );
+    }
 }
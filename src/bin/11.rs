@@ -20,7 +20,7 @@ impl Universe {
         }
     }
 
-    fn find_empty_rows(map: &Vec<Vec<char>>) -> Vec<usize> {
+    fn find_empty_rows(map: &[Vec<char>]) -> Vec<usize> {
         map.iter()
             .enumerate()
             .map(|(i, _)| i)
@@ -28,7 +28,7 @@ impl Universe {
             .collect()
     }
 
-    fn find_empty_cols(map: &Vec<Vec<char>>) -> Vec<usize> {
+    fn find_empty_cols(map: &[Vec<char>]) -> Vec<usize> {
         map.iter()
             .enumerate()
             .map(|(i, _)| i)
@@ -40,13 +40,12 @@ impl Universe {
         self.map
             .iter()
             .enumerate()
-            .map(|(y, row)| {
+            .flat_map(|(y, row)| {
                 row.iter()
                     .enumerate()
                     .filter_map(|(x, c)| Galaxy::new(x, y, c))
                     .collect::<Vec<Galaxy>>()
             })
-            .flatten()
             .collect()
     }
 
@@ -1,55 +1,20 @@
+use advent_of_code::nonogram::{self, Cell};
+
 advent_of_code::solution!(12);
 
 #[derive(Debug)]
 struct SpringSchematic {
-    groups: Vec<char>,
+    groups: Vec<Cell>,
     sizes: Vec<usize>,
 }
 impl SpringSchematic {
     fn combinations(&self) -> u64 {
-        let line = self.groups.clone();
-        let n = line.len();
-        let m = self.sizes.len();
-        let mut dp = &mut vec![vec![0; n + 1]; m + 1];
-        let mut next_dp = &mut vec![vec![0; n + 1]; m + 1];
-
-        dp[m][0] = 1;
-        dp[m - 1][self.sizes[m - 1]] = 1;
-
-        for pos in (0..n).rev() {
-            for group in 0..=m {
-                let max_count = if group == m { 0 } else { self.sizes[group] };
-                for count in 0..=max_count {
-                    next_dp[group][count] = 0;
-                    if matches!(line[pos], '#' | '?') {
-                        next_dp[group][count] += dp[group][count + 1];
-                    }
-                }
-                if matches!(line[pos], '.' | '?') {
-                    next_dp[group][0] += dp[group][0];
-                    if group < m {
-                        next_dp[group][max_count] += dp[group + 1][0];
-                    }
-                }
-            }
-            std::mem::swap(&mut dp, &mut next_dp);
-        }
-
-        dp[0][0]
+        nonogram::count(&self.groups, &self.sizes) as u64
     }
 
-    fn part_two(&self) -> SpringSchematic {
-        let mut groups = self.groups.clone();
-        groups.push('?');
-
-        let mut new_groups: Vec<char> = vec![groups; 5].into_iter().flatten().collect();
-        new_groups.pop();
-        let new_sizes = vec![self.sizes.clone(); 5].into_iter().flatten().collect();
-
-        SpringSchematic {
-            groups: new_groups,
-            sizes: new_sizes,
-        }
+    fn unfold(&self, times: usize) -> SpringSchematic {
+        let (groups, sizes) = nonogram::unfold(&self.groups, &self.sizes, times);
+        SpringSchematic { groups, sizes }
     }
 }
 
@@ -62,7 +27,7 @@ pub fn part_two(input: &str) -> Option<u64> {
     let result = parse_input(input);
     let result = result
         .iter()
-        .map(|s| s.part_two())
+        .map(|s| s.unfold(5))
         .map(|x| x.combinations())
         .sum();
     Some(result)
@@ -73,7 +38,7 @@ fn parse_input(input: &str) -> Vec<SpringSchematic> {
         .lines()
         .map(|line| {
             let parts: &str = line.split(" ").collect::<Vec<&str>>()[0];
-            let condition = parts.chars().collect();
+            let condition = parts.chars().filter_map(Cell::from_char).collect();
             let sizes = line.split(" ").collect::<Vec<&str>>()[1]
                 .split(',')
                 .filter_map(|s| s.parse::<usize>().ok())
@@ -3,48 +3,149 @@ advent_of_code::solution!(13);
 // to that, also add *100 multiplied by the number of rows* above each horizontal line of reflection.
 // In the above example, the first pattern's vertical line has `5` columns to its left and the second
 // pattern's horizontal line has `4` rows above it, a total of `*405*`.
+use advent_of_code::{flag, Point};
+use std::{error::Error, fmt::Display};
+
 #[derive(Debug)]
 struct Landscape {
     cols: Vec<Vec<usize>>,
     rows: Vec<Vec<usize>>,
 }
-impl Landscape {
-    fn solve(&self, diffs: usize) -> usize {
-        let (v, h) = self.find_reflections(diffs);
-        if !v.is_empty() {
-            return v[0];
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Orientation {
+    /// A mirror between two columns.
+    Vertical,
+    /// A mirror between two rows.
+    Horizontal,
+}
+
+/// A candidate line of reflection, and how far off being a real one it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reflection {
+    orientation: Orientation,
+    /// Columns to the left of or rows above the mirror.
+    position: usize,
+    /// Cells that don't match their mirror image.
+    mismatches: usize,
+    /// With a single mismatch, the cell on the left of or above the mirror that needs flipping.
+    smudge: Option<Point>,
+}
+
+impl Reflection {
+    fn summary(&self) -> usize {
+        match self.orientation {
+            Orientation::Vertical => self.position,
+            Orientation::Horizontal => self.position * 100,
         }
-        h[0] * 100
     }
+}
+
+/// An error for a pattern without any line of reflection at the requested number of smudges.
+#[derive(Debug)]
+struct NoReflection {
+    smudges: usize,
+}
 
-    fn find_reflections(&self, diffs: usize) -> (Vec<usize>, Vec<usize>) {
-        let h = Self::find_reflections_helper(&self.rows, diffs);
-        let v = Self::find_reflections_helper(&self.cols, diffs);
-        (v, h)
+impl Error for NoReflection {}
+
+impl Display for NoReflection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "no line of reflection with exactly {} smudges",
+            self.smudges
+        )
+    }
+}
+
+impl Landscape {
+    fn solve(&self, diffs: usize) -> Result<usize, NoReflection> {
+        self.reflection(diffs).map(|r| r.summary())
     }
 
-    fn find_reflections_helper(landscape: &Vec<Vec<usize>>, diffs: usize) -> Vec<usize> {
-        // Helper for find_reflection, since we use it for vert and horizontal
-        (1..landscape.len())
-            .filter(|i| Self::diffs_from_reflection(landscape, *i) as usize == diffs)
-            .map(|i| i as usize)
+    /// The first line of reflection with exactly `diffs` smudges, checking columns before rows.
+    fn reflection(&self, diffs: usize) -> Result<Reflection, NoReflection> {
+        self.reflections()
+            .into_iter()
+            .find(|r| r.mismatches == diffs)
+            .ok_or(NoReflection { smudges: diffs })
+    }
+
+    /// Every place a mirror could go, vertical ones first, with how many cells disagree.
+    fn reflections(&self) -> Vec<Reflection> {
+        let vertical = (1..self.cols.len()).map(|i| (Orientation::Vertical, i, &self.cols));
+        let horizontal = (1..self.rows.len()).map(|i| (Orientation::Horizontal, i, &self.rows));
+        vertical
+            .chain(horizontal)
+            .map(|(orientation, position, lines)| {
+                let mismatches = Self::mismatches(lines, position);
+                // Lines are columns for a vertical mirror, so x and y swap round
+                let smudge = match mismatches[..] {
+                    [(line, index)] => Some(match orientation {
+                        Orientation::Vertical => Point {
+                            x: line as isize,
+                            y: index as isize,
+                        },
+                        Orientation::Horizontal => Point {
+                            x: index as isize,
+                            y: line as isize,
+                        },
+                    }),
+                    _ => None,
+                };
+                Reflection {
+                    orientation,
+                    position,
+                    mismatches: mismatches.len(),
+                    smudge,
+                }
+            })
             .collect()
     }
-    fn diffs_from_reflection(landscape: &Vec<Vec<usize>>, mirror: usize) -> usize {
-        // Find how many spots need to be changed for that mirror to be valid
-        let mut diffs = 0;
+
+    /// The cells before the mirror that differ from their image, as `(line, index in line)`.
+    fn mismatches(landscape: &[Vec<usize>], mirror: usize) -> Vec<(usize, usize)> {
         let length = mirror.min(landscape.len() - mirror);
+        (0..length)
+            .flat_map(|i| {
+                let top_row = &landscape[mirror - 1 - i];
+                let bottom_row = &landscape[mirror + i];
+                top_row
+                    .iter()
+                    .zip(bottom_row)
+                    .enumerate()
+                    .filter(|(_, (a, b))| a != b)
+                    .map(move |(j, _)| (mirror - 1 - i, j))
+            })
+            .collect()
+    }
 
-        for i in 0..length {
-            let top_row = &landscape[mirror - 1 - i];
-            let bottom_row = &landscape[mirror + i];
-            for (a, b) in top_row.iter().zip(bottom_row) {
-                if a != b {
-                    diffs += 1;
+    /// The pattern with the mirror drawn in as `|` or `-`, and the smudge marked with `X`.
+    fn render(&self, reflection: &Reflection) -> String {
+        let mut output = String::new();
+        for (y, row) in self.rows.iter().enumerate() {
+            if reflection.orientation == Orientation::Horizontal && y == reflection.position {
+                output.push_str(&"-".repeat(row.len()));
+                output.push('\n');
+            }
+            for (x, cell) in row.iter().enumerate() {
+                if reflection.orientation == Orientation::Vertical && x == reflection.position {
+                    output.push('|');
                 }
+                let smudged = reflection.smudge
+                    == Some(Point {
+                        x: x as isize,
+                        y: y as isize,
+                    });
+                output.push(match (smudged, cell) {
+                    (true, _) => 'X',
+                    (false, 1) => '#',
+                    (false, _) => '.',
+                });
             }
+            output.push('\n');
         }
-        diffs
+        output
     }
 
     fn new(input: &str) -> Landscape {
@@ -71,12 +172,27 @@ impl Landscape {
 
 pub fn part_one(input: &str) -> Option<usize> {
     let landscapes = parse_input(input);
-    Some(landscapes.iter().map(|l| l.solve(0)).sum())
+    landscapes
+        .iter()
+        .map(|l| l.solve(0))
+        .sum::<Result<usize, _>>()
+        .ok()
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let landscapes = parse_input(input);
-    Some(landscapes.iter().map(|l| l.solve(1)).sum())
+    if flag("--print") {
+        for landscape in &landscapes {
+            if let Ok(reflection) = landscape.reflection(1) {
+                println!("{}", landscape.render(&reflection));
+            }
+        }
+    }
+    landscapes
+        .iter()
+        .map(|l| l.solve(1))
+        .sum::<Result<usize, _>>()
+        .ok()
 }
 
 fn parse_input(input: &str) -> Vec<Landscape> {
@@ -104,4 +220,47 @@ mod tests {
         let result = parse_input(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result.len(), 2);
     }
+
+    #[test]
+    fn test_reflections() {
+        let landscapes = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let all = landscapes[0].reflections();
+        assert_eq!(all.len(), 8 + 6);
+        let found = landscapes[0].reflection(0).unwrap();
+        assert_eq!(
+            (found.orientation, found.position),
+            (Orientation::Vertical, 5)
+        );
+
+        let smudged = landscapes[0].reflection(1).unwrap();
+        assert_eq!(smudged.orientation, Orientation::Horizontal);
+        assert_eq!(smudged.position, 3);
+        assert_eq!(smudged.smudge, Some(Point { x: 0, y: 0 }));
+
+        let smudged = landscapes[1].reflection(1).unwrap();
+        assert_eq!(smudged.position, 1);
+        assert_eq!(smudged.smudge, Some(Point { x: 4, y: 0 }));
+    }
+
+    #[test]
+    fn test_no_reflection() {
+        let landscape = Landscape::new("#.\n.#\n");
+        let error = landscape.solve(0).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no line of reflection with exactly 0 smudges"
+        );
+        assert_eq!(part_one("#.\n.#\n"), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let landscapes = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let smudged = landscapes[1].reflection(1).unwrap();
+        let result = landscapes[1].render(&smudged);
+        assert_eq!(
+            result.lines().take(3).collect::<Vec<_>>(),
+            vec!["#...X#..#", "---------", "#....#..#"]
+        );
+    }
 }
@@ -1,140 +1,167 @@
-use std::collections::HashMap;
+use std::fmt::Display;
+
+use advent_of_code::{cycle, Direction};
 
 advent_of_code::solution!(14);
 
+/// The platform as one bitboard per row, with bit `x` standing for column `x`.
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Dish {
-    rows: Vec<Vec<char>>,
-    cols: Vec<Vec<char>>,
+    width: usize,
+    /// Round rocks, which roll.
+    rocks: Vec<u128>,
+    /// Cube-shaped rocks, which don't.
+    walls: Vec<u128>,
 }
 impl Dish {
-    fn solve(&mut self, direction: (isize, isize)) -> Option<usize> {
-        self.roll(direction);
-        Some(self.weight())
+    fn spin_cycle(&mut self) {
+        self.tilt(Direction::Up);
+        self.tilt(Direction::Left);
+        self.tilt(Direction::Down);
+        self.tilt(Direction::Right);
     }
 
-    fn solve_two(&mut self) -> Option<usize> {
-        let cycles_left = self.find_cycles_left();
-        for _ in 0..cycles_left {
-            self.roll_cycle();
+    fn tilt(&mut self, direction: Direction) {
+        match direction {
+            Direction::Up => self.tilt_vertical(|y| y.checked_sub(1), 0..self.rocks.len()),
+            Direction::Down => {
+                let height = self.rocks.len();
+                self.tilt_vertical(|y| Some(y + 1).filter(|&y| y < height), (0..height).rev())
+            }
+            Direction::Left | Direction::Right => {
+                for y in 0..self.rocks.len() {
+                    self.rocks[y] = self.tilt_row(y, direction == Direction::Left);
+                }
+            }
         }
-        Some(self.weight())
-    }
-
-    fn find_cycles_left(&mut self) -> usize {
-        let (start, cycles) = self.find_cycle_start();
-        let cycle_length = cycles - start;
-        (1_000_000_000 - cycles) % (cycle_length)
     }
 
-    fn find_cycle_start(&mut self) -> (usize, usize) {
-        let mut states = HashMap::new();
-
-        for cycles in 1..1_000_000_000 {
-            self.roll_cycle();
-            let current_state = self.get_state();
-            if let Some(start) = states.get(&current_state) {
-                return (*start as usize, cycles as usize);
+    /// Rolls every column at once, settling rows in `order` so each one only has to fall onto
+    /// rows that have already stopped moving.
+    fn tilt_vertical(
+        &mut self,
+        towards: impl Fn(usize) -> Option<usize>,
+        order: impl Iterator<Item = usize>,
+    ) {
+        for y in order {
+            let mut moving = self.rocks[y];
+            let mut from = y;
+            while let Some(to) = towards(from) {
+                moving &= !(self.rocks[to] | self.walls[to]);
+                if moving == 0 {
+                    break;
+                }
+                self.rocks[from] ^= moving;
+                self.rocks[to] |= moving;
+                from = to;
             }
-            states.insert(current_state.clone(), cycles);
         }
-        (0, 1_000_000_000)
-    }
-
-    fn roll_cycle(&mut self) -> () {
-        self.roll((0, 1));
-        self.roll((1, 0));
-        self.roll((0, -1));
-        self.roll((-1, 0));
     }
 
-    fn roll(&mut self, (row_diff, col_diff): (isize, isize)) -> () {
-        if row_diff != 0 {
-            self.rows = self.roll_helper(row_diff, &self.rows);
-            self.cols = transpose(&self.rows);
-        } else {
-            self.cols = self.roll_helper(col_diff, &self.cols);
-            self.rows = transpose(&self.cols);
+    /// The rocks of row `y` once they've rolled towards column 0, or away from it, piling up
+    /// against the walls between them.
+    fn tilt_row(&self, y: usize, towards_zero: bool) -> u128 {
+        let (rocks, walls) = (self.rocks[y], self.walls[y]);
+        let mut result = 0;
+        let mut start = 0;
+        while start < self.width {
+            let end = (walls >> start << start)
+                .trailing_zeros()
+                .min(self.width as u32) as usize;
+            let count = (rocks & span(start, end)).count_ones() as usize;
+            result |= if towards_zero {
+                span(start, start + count)
+            } else {
+                span(end - count, end)
+            };
+            start = end + 1;
         }
+        result
+    }
 
-        assert_eq!(self.rows, transpose(&self.cols));
+    fn weight(&self) -> usize {
+        let height = self.rocks.len();
+        self.rocks
+            .iter()
+            .enumerate()
+            .map(|(y, row)| row.count_ones() as usize * (height - y))
+            .sum()
     }
 
-    fn roll_helper(&self, direction: isize, section: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-        if direction == 0 {
-            return section.clone();
-        }
-        (0..section[0].len())
-            .map(|i| {
-                let mut new_sec = vec![];
-                for slide in section[i].split(|&c| c == '#') {
-                    let rocks = slide.iter().filter(|&&c| c == 'O').count();
-                    let mut new_slide = vec![];
-
-                    if direction > 0 {
-                        new_slide.extend(vec!['O'; rocks]);
-                        new_slide.extend(vec!['.'; slide.len() - rocks]);
-                    } else {
-                        new_slide.extend(vec!['.'; slide.len() - rocks]);
-                        new_slide.extend(vec!['O'; rocks]);
-                    }
-                    new_sec.extend(new_slide);
-                    new_sec.push('#');
-                }
-                new_sec.pop();
-                new_sec
-            })
-            .collect()
+    /// A 128 bit digest of where the round rocks are, so the cycle search doesn't need to keep
+    /// every board it has seen.
+    fn fingerprint(&self) -> u128 {
+        self.rocks.iter().fold(0, |hash, &row| {
+            (hash.rotate_left(5) ^ row).wrapping_mul(0x9E37_79B9_7F4A_7C15_F39C_C060_5CED_C835)
+        })
     }
+}
 
-    fn get_state(&self) -> Vec<Vec<char>> {
-        self.rows.clone()
+/// The dish the way the puzzle draws it.
+impl Display for Dish {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (rocks, walls) in self.rocks.iter().zip(&self.walls) {
+            for x in 0..self.width {
+                let c = match (rocks >> x & 1, walls >> x & 1) {
+                    (1, _) => 'O',
+                    (_, 1) => '#',
+                    _ => '.',
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
+}
 
-    fn weight(&self) -> usize {
-        let height = self.cols.len();
-        self.cols
-            .iter()
-            .map(|c| {
-                c.iter()
-                    .enumerate()
-                    .map(|(i, &c)| match c {
-                        'O' => height - i,
-                        _ => 0,
-                    })
-                    .sum::<usize>()
-            })
-            .sum::<usize>() as usize
+/// Bits `start..end` set.
+fn span(start: usize, end: usize) -> u128 {
+    if end <= start {
+        0
+    } else {
+        (u128::MAX >> (128 - (end - start))) << start
     }
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
     let mut dish = parse_input(input);
-    dish.solve((0, 1))
+    dish.tilt(Direction::Up);
+    Some(dish.weight())
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let mut dish = parse_input(input);
-    dish.solve_two()
+    let dish = parse_input(input);
+    let spin = |dish: &Dish| {
+        let mut dish = dish.clone();
+        dish.spin_cycle();
+        dish
+    };
+    let cycle = cycle::hashed(dish, spin, Dish::fingerprint, 1_000_000_000);
+    Some(cycle.state.weight())
 }
 
 fn parse_input(input: &str) -> Dish {
-    let rows: Vec<Vec<char>> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| l.chars().collect())
-        .collect();
-
-    let cols = (0..rows[0].len())
-        .map(|col| (0..rows.len()).map(|row| rows[row][col]).collect())
-        .collect();
-
-    Dish { rows, cols }
-}
+    let rows: Vec<&str> = input.lines().filter(|l| !l.is_empty()).collect();
+    let width = rows[0].len();
+    assert!(width <= 128, "rows have to fit in a u128");
+
+    let board = |rock: char| {
+        rows.iter()
+            .map(|row| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == rock)
+                    .fold(0, |board, (x, _)| board | 1 << x)
+            })
+            .collect()
+    };
 
-fn transpose(v: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    (0..v[0].len())
-        .map(|col| (0..v.len()).map(|row| v[row][col]).collect())
-        .collect()
+    Dish {
+        width,
+        rocks: board('O'),
+        walls: board('#'),
+    }
 }
 
 #[cfg(test)]
@@ -155,7 +182,36 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        let result = parse_input(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result.cols.len(), 10);
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = parse_input(&input);
+        assert_eq!(result.width, 10);
+        assert_eq!(result.rocks.len(), 10);
+        assert_eq!(result.to_string(), input);
+    }
+
+    #[test]
+    fn test_tilt() {
+        let mut dish = parse_input("O.#O.\n.O..O\n#..O.\n");
+        dish.tilt(Direction::Up);
+        assert_eq!(dish.to_string(), "OO#OO\n...O.\n#....\n");
+        dish.tilt(Direction::Right);
+        assert_eq!(dish.to_string(), "OO#OO\n....O\n#....\n");
+        dish.tilt(Direction::Down);
+        assert_eq!(dish.to_string(), "..#..\nO...O\n#O.OO\n");
+        dish.tilt(Direction::Left);
+        assert_eq!(dish.to_string(), "..#..\nOO...\n#OOO.\n");
+    }
+
+    #[test]
+    fn test_spin_cycle() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut dish = parse_input(&input);
+        dish.spin_cycle();
+        assert_eq!(
+            dish.to_string().lines().take(3).collect::<Vec<_>>(),
+            vec![".....#....", "....#...O#", "...OO##..."]
+        );
+        let other = parse_input(&input);
+        assert_ne!(dish.fingerprint(), other.fingerprint());
     }
 }
@@ -1,32 +1,31 @@
-use std::collections::HashMap;
+use std::fmt::Display;
+
+use advent_of_code::flag;
 
 advent_of_code::solution!(15);
 
 #[derive(Debug, Clone)]
 struct Instruction {
-    box_number: usize,
     label: String,
     operation: char,
-    focus: Option<u32>,
+    focus: Option<usize>,
     hash: usize,
 }
 impl Instruction {
     fn new(input: &str) -> Instruction {
         let hash = hash_instruction(input);
         let operation = if input.contains('=') { '=' } else { '-' };
-        let operation_idx = input.find(operation);
+        let operation_idx = input.find(operation).unwrap();
 
         let focus = if operation == '=' {
-            input.chars().filter_map(|c| c.to_digit(10)).last()
+            input[operation_idx + 1..].parse().ok()
         } else {
             None
         };
 
-        let label = input[0..operation_idx.unwrap()].to_string();
-        let box_number = hash_instruction(&label);
+        let label = input[0..operation_idx].to_string();
 
         Instruction {
-            box_number,
             label,
             operation,
             focus,
@@ -40,52 +39,108 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    Some(
-        parse_map(parse_input(input))
+    let print = flag("--print");
+    let mut boxes = LensBoxes::new();
+    for ins in parse_input(input) {
+        boxes.apply(&ins);
+        if print {
+            println!("After \"{ins}\":\n{boxes}");
+        }
+    }
+    Some(boxes.focusing_power())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lens {
+    label: String,
+    focus: usize,
+}
+
+/// The 256 boxes lenses go in, each holding its lenses front to back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LensBoxes {
+    boxes: Vec<Vec<Lens>>,
+}
+impl LensBoxes {
+    fn new() -> LensBoxes {
+        LensBoxes {
+            boxes: vec![vec![]; 256],
+        }
+    }
+
+    /// Swaps out the lens with the same label in place, or puts the lens at the back of its box.
+    fn insert(&mut self, label: &str, focus: usize) {
+        let lenses = &mut self.boxes[hash_instruction(label)];
+        match lenses.iter_mut().find(|lens| lens.label == label) {
+            Some(existing) => existing.focus = focus,
+            None => lenses.push(Lens {
+                label: label.to_string(),
+                focus,
+            }),
+        }
+    }
+
+    /// Takes out the lens with this label, if there is one, moving the ones behind it forward.
+    fn remove(&mut self, label: &str) {
+        self.boxes[hash_instruction(label)].retain(|lens| lens.label != label);
+    }
+
+    fn apply(&mut self, ins: &Instruction) {
+        match (ins.operation, ins.focus) {
+            ('=', Some(focus)) => self.insert(&ins.label, focus),
+            ('-', _) => self.remove(&ins.label),
+            _ => unreachable!(),
+        }
+    }
+
+    /// The sum over every lens of its box number and its slot, both counting from one, times its
+    /// focal length.
+    fn focusing_power(&self) -> usize {
+        self.boxes
             .iter()
-            .map(|(box_number, lenses)| {
+            .enumerate()
+            .flat_map(|(box_number, lenses)| {
                 lenses
                     .iter()
                     .enumerate()
-                    .map(|(i, ins)| (box_number + 1) * (i + 0) * ins.focus.unwrap() as usize)
-                    .sum::<usize>()
+                    .map(move |(slot, lens)| (box_number + 1) * (slot + 1) * lens.focus)
             })
-            .sum(),
-    )
+            .sum()
+    }
 }
 
-fn parse_map(input: Vec<Instruction>) -> HashMap<usize, Vec<Instruction>> {
-    let mut map: HashMap<usize, Vec<Instruction>> = HashMap::new();
-    for ins in input {
-        match ins.operation {
-            '-' => {
-                if let Some(x) = map.get_mut(&ins.box_number) {
-                    x.retain(|i| i.label != ins.label)
-                }
+/// Lists the boxes that have lenses in them, like `Box 0: [rn 1] [cm 2]`.
+impl Display for LensBoxes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (box_number, lenses) in self.boxes.iter().enumerate() {
+            if lenses.is_empty() {
+                continue;
             }
-            '=' => {
-                map.entry(ins.box_number)
-                    .and_modify(|lenses| {
-                        if let Some(existing) = lenses.iter_mut().find(|i| i.label == ins.label) {
-                            existing.focus = ins.focus;
-                        } else {
-                            lenses.push(ins.clone());
-                        }
-                    })
-                    .or_insert_with(|| vec![ins]);
+            write!(f, "Box {box_number}:")?;
+            for lens in lenses {
+                write!(f, " [{} {}]", lens.label, lens.focus)?;
             }
-            _ => unreachable!(),
+            writeln!(f)?;
         }
+        Ok(())
     }
+}
 
-    map
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.label, self.operation)?;
+        if let Some(focus) = self.focus {
+            write!(f, "{focus}")?;
+        }
+        Ok(())
+    }
 }
 
 fn parse_input(input: &str) -> Vec<Instruction> {
     input
         .split(&[',', '\n'])
         .filter(|&s| !s.is_empty())
-        .map(|s| Instruction::new(s))
+        .map(Instruction::new)
         .collect()
 }
 
@@ -123,7 +178,7 @@ mod tests {
         let input = "HASH=9";
         let instruction = Instruction::new(input);
         assert_eq!(instruction.operation, '=');
-        assert_eq!(instruction.box_number, 52);
+        assert_eq!(hash_instruction(&instruction.label), 52);
         assert_eq!(instruction.label, "HASH".to_string());
         assert_eq!(instruction.focus, Some(9));
         let input = "HASH-";
@@ -133,9 +188,38 @@ mod tests {
     }
 
     #[test]
-    fn parse_map_test() {
-        let input = parse_input(&advent_of_code::template::read_file("examples", DAY));
-        let map = parse_map(input);
-        assert_eq!(map.keys().len(), 3);
+    fn test_lens_boxes() {
+        let mut boxes = LensBoxes::new();
+        boxes.insert("rn", 1);
+        boxes.insert("qp", 3);
+        boxes.insert("cm", 2);
+        boxes.insert("qp", 4);
+        assert_eq!(boxes.to_string(), "Box 0: [rn 1] [cm 2]\nBox 1: [qp 4]\n");
+        boxes.remove("rn");
+        boxes.remove("ab");
+        assert_eq!(boxes.to_string(), "Box 0: [cm 2]\nBox 1: [qp 4]\n");
+        assert_eq!(boxes.focusing_power(), 2 + 2 * 4);
+    }
+
+    #[test]
+    fn test_walkthrough() {
+        let steps = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let mut boxes = LensBoxes::new();
+        let trace: Vec<String> = steps
+            .iter()
+            .map(|ins| {
+                boxes.apply(ins);
+                format!("After \"{ins}\":\n{boxes}")
+            })
+            .collect();
+
+        assert_eq!(trace[0], "After \"rn=1\":\nBox 0: [rn 1]\n");
+        assert_eq!(trace[1], "After \"cm-\":\nBox 0: [rn 1]\n");
+        assert_eq!(trace[4], "After \"qp-\":\nBox 0: [rn 1] [cm 2]\n");
+        assert_eq!(
+            trace[10],
+            "After \"ot=7\":\nBox 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]\n"
+        );
+        assert_eq!(boxes.focusing_power(), 145);
     }
 }
@@ -1,27 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
-use itertools::Itertools;
-use rayon::iter::IntoParallelRefIterator;
-use rayon::iter::ParallelIterator;
-advent_of_code::solution!(16);
+use advent_of_code::{Direction, Point};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl Direction {
-    fn to_tuple(&self) -> (isize, isize) {
-        match self {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        }
-    }
-}
+advent_of_code::solution!(16);
 
 #[derive(Debug, Clone, Copy)]
 enum MirrorType {
@@ -67,203 +48,252 @@ impl MirrorType {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-struct Beam {
-    loc: (isize, isize),
-    dir: Direction,
+/// The tiles a beam passes through, one bit per tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Tiles {
+    bits: Vec<u64>,
 }
-impl Beam {
-    fn step(&self) -> Beam {
-        let (dx, dy) = self.dir.to_tuple();
-        let loc = (self.loc.0 + dx, self.loc.1 + dy);
-        let dir = self.dir;
-        Beam { loc, dir }
+impl Tiles {
+    fn new(size: usize) -> Tiles {
+        Tiles {
+            bits: vec![0; size.div_ceil(64)],
+        }
     }
 
-    fn reflect(&self, mirror: &MirrorType) -> Vec<Beam> {
-        mirror
-            .reflect(self.dir)
-            .iter()
-            .map(|dir| Beam {
-                loc: self.loc,
-                dir: dir.clone(),
-            })
-            .collect()
+    fn insert(&mut self, index: usize) {
+        self.bits[index / 64] |= 1 << (index % 64);
     }
 
-    fn in_bounds(&self, height: usize, width: usize) -> bool {
-        let (x, y) = self.loc;
-        let out = x < 0 || x >= width as isize || y < 0 || y >= height as isize;
-        !out
+    fn union_with(&mut self, other: &Tiles) {
+        for (a, b) in self.bits.iter_mut().zip(&other.bits) {
+            *a |= b;
+        }
     }
 
-    fn default_beam() -> Beam {
-        Beam {
-            loc: (0, 0),
-            dir: Direction::Right,
-        }
+    fn count(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
     }
 }
 
-#[derive(Clone)]
+/// A beam's straight and reflected path up to the next splitter it's split by, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    tiles: Tiles,
+    /// The splitter the segment ends at, or `None` if it leaves the grid.
+    end: Option<usize>,
+}
+
 struct Contraption {
     height: usize,
     width: usize,
-    beams: Vec<Beam>,
-    mirrors: HashMap<(isize, isize), MirrorType>,
-    energized: HashSet<Beam>,
+    mirrors: HashMap<Point, MirrorType>,
 }
 impl Contraption {
-    fn new(height: usize, width: usize, mirrors: HashMap<(isize, isize), MirrorType>) -> Self {
-        let beam = Beam::default_beam();
-        let beams = if let Some(mirror) = mirrors.get(&beam.loc) {
-            beam.reflect(mirror)
-        } else {
-            vec![beam]
-        };
-
-        Contraption {
-            height,
-            width,
-            beams,
-            mirrors,
-            energized: HashSet::new(),
-        }
+    fn in_bounds(&self, point: Point) -> bool {
+        (0..self.width as isize).contains(&point.x) && (0..self.height as isize).contains(&point.y)
+    }
+
+    fn tile_index(&self, point: Point) -> usize {
+        point.y as usize * self.width + point.x as usize
     }
 
-    fn reflect_beam(&self, beam: Beam) -> Vec<Beam> {
-        if let Some(mirror) = self.mirrors.get(&beam.loc) {
-            beam.reflect(mirror)
-        } else {
-            vec![beam]
+    /// Follows a beam leaving `from` in direction `dir` until it reaches a splitter that splits it,
+    /// or runs off the grid. `from` itself counts as energized only if it's on the grid, so beams
+    /// can come in from just outside an edge.
+    fn segment(
+        &self,
+        from: Point,
+        mut dir: Direction,
+        splitters: &HashMap<Point, usize>,
+    ) -> Segment {
+        let mut tiles = Tiles::new(self.width * self.height);
+        if self.in_bounds(from) {
+            tiles.insert(self.tile_index(from));
+        }
+        let mut seen = HashSet::new();
+        let mut point = from.moved(dir, 1);
+        while self.in_bounds(point) && seen.insert((point, dir)) {
+            tiles.insert(self.tile_index(point));
+            if let Some(mirror) = self.mirrors.get(&point) {
+                match mirror.reflect(dir)[..] {
+                    [turned] => dir = turned,
+                    _ => {
+                        return Segment {
+                            tiles,
+                            end: Some(splitters[&point]),
+                        }
+                    }
+                }
+            }
+            point = point.moved(dir, 1);
         }
+        Segment { tiles, end: None }
     }
 
-    fn step(&mut self) {
-        self.beams.iter().for_each(|b| {
-            self.energized.insert(*b);
-        });
+    /// Compiles the mirrors into a graph between splitters, and works out everything each
+    /// splitter ends up energizing once.
+    fn beam_graph(&self) -> BeamGraph {
+        let positions: Vec<Point> = self
+            .mirrors
+            .iter()
+            .filter(|(_, mirror)| matches!(mirror, MirrorType::Vertical | MirrorType::Horizontal))
+            .map(|(&point, _)| point)
+            .collect();
+        let splitters: HashMap<Point, usize> =
+            positions.iter().enumerate().map(|(i, &p)| (p, i)).collect();
 
-        self.beams = self
-            .beams
+        // A splitter sends beams out the same two ways whichever side it's hit from
+        let segments: Vec<Vec<Segment>> = positions
             .iter()
-            .map(|&b| b.step())
-            .map(|b| self.reflect_beam(b))
-            .flatten()
-            .filter(|beam| beam.in_bounds(self.height, self.width))
-            .filter(|beam| !self.energized.contains(beam))
+            .map(|&point| {
+                let outgoing = match self.mirrors[&point] {
+                    MirrorType::Vertical => [Direction::Up, Direction::Down],
+                    _ => [Direction::Left, Direction::Right],
+                };
+                outgoing
+                    .iter()
+                    .map(|&dir| self.segment(point, dir, &splitters))
+                    .collect()
+            })
             .collect();
+
+        let components = strongly_connected(&segments);
+        let mut component_tiles: Vec<Tiles> = vec![];
+        let mut component_of = vec![0; positions.len()];
+        for (c, members) in components.iter().enumerate() {
+            members.iter().for_each(|&i| component_of[i] = c);
+            // Components come out with the ones they lead to first, so those are already done
+            let mut tiles = Tiles::new(self.width * self.height);
+            for segment in members.iter().flat_map(|&i| &segments[i]) {
+                tiles.union_with(&segment.tiles);
+                if let Some(next) = segment.end {
+                    if component_of[next] != c {
+                        tiles.union_with(&component_tiles[component_of[next]]);
+                    }
+                }
+            }
+            component_tiles.push(tiles);
+        }
+
+        BeamGraph {
+            splitters,
+            energized: component_of
+                .iter()
+                .map(|&c| component_tiles[c].clone())
+                .collect(),
+        }
+    }
+
+    /// Every beam coming in from outside an edge, heading into the grid.
+    fn edge_starts(&self) -> Vec<(Point, Direction)> {
+        let (width, height) = (self.width as isize, self.height as isize);
+        let rows = (0..height).flat_map(|y| {
+            [
+                (Point { x: -1, y }, Direction::Right),
+                (Point { x: width, y }, Direction::Left),
+            ]
+        });
+        let columns = (0..width).flat_map(|x| {
+            [
+                (Point { x, y: -1 }, Direction::Down),
+                (Point { x, y: height }, Direction::Up),
+            ]
+        });
+        rows.chain(columns).collect()
     }
+}
 
-    fn energized_tiles(&mut self) -> Option<usize> {
-        while !self.beams.is_empty() {
-            self.step();
+/// The splitters, along with every tile energized once a splitter has split a beam.
+struct BeamGraph {
+    splitters: HashMap<Point, usize>,
+    energized: Vec<Tiles>,
+}
+impl BeamGraph {
+    fn energized_tiles(&self, contraption: &Contraption, from: Point, dir: Direction) -> usize {
+        let mut segment = contraption.segment(from, dir, &self.splitters);
+        if let Some(end) = segment.end {
+            segment.tiles.union_with(&self.energized[end]);
         }
-        Some(self.energized.iter().map(|b| b.loc).unique().count() as usize)
+        segment.tiles.count()
     }
+}
 
-    fn init_beams(&self, loc: (isize, isize)) -> Vec<Beam> {
-        let beams = self.init_beam(loc);
-        beams
-            .iter()
-            .map(|beam| self.reflect_beam(*beam))
-            .flatten()
-            .collect()
+/// Tarjan's algorithm over the splitters, returning the components in reverse topological order.
+fn strongly_connected(segments: &[Vec<Segment>]) -> Vec<Vec<usize>> {
+    struct Tarjan<'a> {
+        segments: &'a [Vec<Segment>],
+        visited: usize,
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        components: Vec<Vec<usize>>,
     }
+    impl Tarjan<'_> {
+        fn visit(&mut self, v: usize) {
+            let index = self.visited;
+            self.visited += 1;
+            self.index[v] = Some(index);
+            self.low[v] = index;
+            self.stack.push(v);
+            self.on_stack[v] = true;
 
-    fn init_beam(&self, loc: (isize, isize)) -> Vec<Beam> {
-        let (width, height) = (self.width as isize - 1, self.height as isize - 1);
-        match loc {
-            l if l == (0, 0) => vec![
-                Beam {
-                    loc,
-                    dir: Direction::Right,
-                },
-                Beam {
-                    loc,
-                    dir: Direction::Down,
-                },
-            ],
-            l if l == (height, 0) => vec![
-                Beam {
-                    loc,
-                    dir: Direction::Left,
-                },
-                Beam {
-                    loc,
-                    dir: Direction::Down,
-                },
-            ],
-            l if l == (0, height) => vec![
-                Beam {
-                    loc,
-                    dir: Direction::Right,
-                },
-                Beam {
-                    loc,
-                    dir: Direction::Up,
-                },
-            ],
-            l if l == (height, width) => vec![
-                Beam {
-                    loc,
-                    dir: Direction::Left,
-                },
-                Beam {
-                    loc,
-                    dir: Direction::Up,
-                },
-            ],
-            (0, _) => vec![Beam {
-                loc,
-                dir: Direction::Right,
-            }],
-            (_, 0) => vec![Beam {
-                loc,
-                dir: Direction::Down,
-            }],
-            l if l.0 == width => vec![Beam {
-                loc,
-                dir: Direction::Up,
-            }],
-            l if l.1 == height => vec![Beam {
-                loc,
-                dir: Direction::Left,
-            }],
-            (_, _) => vec![],
+            for w in self.segments[v].iter().filter_map(|s| s.end) {
+                match self.index[w] {
+                    None => {
+                        self.visit(w);
+                        self.low[v] = self.low[v].min(self.low[w]);
+                    }
+                    Some(i) if self.on_stack[w] => self.low[v] = self.low[v].min(i),
+                    Some(_) => {}
+                }
+            }
+
+            if Some(self.low[v]) == self.index[v] {
+                let mut component = vec![];
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let n = segments.len();
+    let mut tarjan = Tarjan {
+        segments,
+        visited: 0,
+        index: vec![None; n],
+        low: vec![0; n],
+        stack: vec![],
+        on_stack: vec![false; n],
+        components: vec![],
+    };
+    for v in 0..n {
+        if tarjan.index[v].is_none() {
+            tarjan.visit(v);
         }
     }
+    tarjan.components
 }
+
 pub fn part_one(input: &str) -> Option<usize> {
-    let mut contraption = parse_input(input);
-    // while contraption.beams.len() > 0 {
-    contraption.energized_tiles()
+    let contraption = parse_input(input);
+    let graph = contraption.beam_graph();
+    Some(graph.energized_tiles(&contraption, Point { x: -1, y: 0 }, Direction::Right))
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
     let contraption = parse_input(input);
-    let (w, h) = (contraption.width, contraption.height);
-    let starts: Vec<_> = (0..w)
-        .cartesian_product(0..h)
-        .map(|(x, y)| (x, y))
-        .filter(|(x, y)| *x == 0 || *x == w - 1 || *y == 0 || *y == h - 1)
-        .collect();
-
-    starts
-        .par_iter()
-        .map(|(x, y)| {
-            let beams = contraption.init_beams((*x as isize, *y as isize));
-            beams
-                .iter()
-                .map(|beam| {
-                    let mut contraption = contraption.clone();
-                    contraption.beams = vec![*beam];
-                    contraption.energized = HashSet::new();
-                    contraption.energized_tiles().unwrap()
-                })
-                .max()
-        })
-        .flatten()
+    let graph = contraption.beam_graph();
+    contraption
+        .edge_starts()
+        .into_iter()
+        .map(|(from, dir)| graph.energized_tiles(&contraption, from, dir))
         .max()
 }
 
@@ -271,18 +301,22 @@ fn parse_input(input: &str) -> Contraption {
     let mirrors = input
         .lines()
         .enumerate()
-        .map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(x, c)| ((x as isize, y as isize), MirrorType::from_char(c)))
-                .filter(|(_, m)| m.is_some())
-                .map(|(loc, mirror)| (loc, mirror.unwrap()))
-                .collect::<HashMap<(isize, isize), MirrorType>>()
+        .flat_map(|(y, line)| {
+            line.chars().enumerate().filter_map(move |(x, c)| {
+                let point = Point {
+                    x: x as isize,
+                    y: y as isize,
+                };
+                MirrorType::from_char(c).map(|mirror| (point, mirror))
+            })
         })
-        .flatten()
         .collect();
     let (width, height) = (input.lines().next().unwrap().len(), input.lines().count());
-    Contraption::new(height, width, mirrors)
+    Contraption {
+        height,
+        width,
+        mirrors,
+    }
 }
 
 #[cfg(test)]
@@ -306,4 +340,51 @@ mod tests {
         let result = parse_input(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result.mirrors.len(), 23);
     }
+
+    /// Follows every beam one tile at a time, the slow way.
+    fn simulate(contraption: &Contraption, from: Point, dir: Direction) -> usize {
+        let mut seen = HashSet::new();
+        let mut beams = vec![(from.moved(dir, 1), dir)];
+        while let Some((point, dir)) = beams.pop() {
+            if !contraption.in_bounds(point) || !seen.insert((point, dir)) {
+                continue;
+            }
+            let dirs = match contraption.mirrors.get(&point) {
+                Some(mirror) => mirror.reflect(dir),
+                None => vec![dir],
+            };
+            beams.extend(dirs.into_iter().map(|d| (point.moved(d, 1), d)));
+        }
+        seen.iter()
+            .map(|(point, _)| point)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    #[test]
+    fn test_non_square() {
+        let tall = parse_input(".|.\n...\n.-.\n...\n\\./\n");
+        assert_eq!((tall.width, tall.height), (3, 5));
+        assert_eq!(part_one(".|.\n...\n.-.\n...\n\\./\n"), Some(6));
+        assert_eq!(tall.edge_starts().len(), 2 * 3 + 2 * 5);
+
+        let wide = ".-..\\..|\n.......-\n.\\..-/..\n";
+        assert_eq!(part_two(wide), Some(14));
+
+        for input in [
+            ".|.\n...\n.-.\n...\n\\./\n",
+            wide,
+            &advent_of_code::template::read_file("examples", DAY),
+        ] {
+            let contraption = parse_input(input);
+            let graph = contraption.beam_graph();
+            for (from, dir) in contraption.edge_starts() {
+                assert_eq!(
+                    graph.energized_tiles(&contraption, from, dir),
+                    simulate(&contraption, from, dir),
+                    "starting from {from:?} going {dir:?}"
+                );
+            }
+        }
+    }
 }
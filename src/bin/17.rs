@@ -1,56 +1,95 @@
-use std::{cmp::Ordering, collections::BinaryHeap};
+use advent_of_code::{flag, search::dijkstra, Direction, Point};
 
 advent_of_code::solution!(17);
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Crucible {
+    position: Point,
+    direction: Direction,
+    momentum: u8,
+}
+
+/// A way a crucible can change direction, relative to the way it's going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
     Left,
     Right,
 }
+impl Turn {
+    fn apply(self, direction: Direction) -> Direction {
+        match self {
+            Turn::Left => direction.turn_left(),
+            Turn::Right => direction.turn_right(),
+        }
+    }
+}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct Point {
-    x: isize,
-    y: isize,
+/// How a crucible is allowed to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rules {
+    /// Blocks it has to go in a straight line before it can turn or stop.
+    min_run: u8,
+    /// Blocks it can go in a straight line at most.
+    max_run: u8,
+    /// Which ways it can turn.
+    turns: &'static [Turn],
+    /// Whether it can turn back the way it came. Neither crucible in the puzzle can.
+    reverse: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Crucible {
-    position: Option<Point>,
+const CRUCIBLE: Rules = Rules {
+    min_run: 0,
+    max_run: 3,
+    turns: &[Turn::Left, Turn::Right],
+    reverse: false,
+};
+
+const ULTRA_CRUCIBLE: Rules = Rules {
+    min_run: 4,
+    max_run: 10,
+    turns: &[Turn::Left, Turn::Right],
+    reverse: false,
+};
+
+/// A block the crucible moves into, and the heat lost there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Step {
+    position: Point,
     direction: Direction,
-    momentum: u8,
     heat_loss: usize,
 }
 
-struct CrucibleSolver {
-    grid: Vec<Vec<usize>>,
-}
-
-impl Ord for Crucible {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .heat_loss
-            .cmp(&self.heat_loss)
-            .then_with(|| self.position.cmp(&other.position))
-    }
+/// The least heat loss from the start to the target, along with a path that achieves it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Route {
+    heat_loss: usize,
+    steps: Vec<Step>,
 }
 
-impl PartialOrd for Crucible {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+struct CrucibleSolver {
+    grid: Vec<Vec<usize>>,
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let crucible = parse_input(input);
-    Some(crucible.heat_loss(0, 3))
+    solve(input, CRUCIBLE)
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let crucible = parse_input(input);
-    Some(crucible.heat_loss(4, 10))
+    solve(input, ULTRA_CRUCIBLE)
+}
+
+/// Top left to bottom right, like the puzzle.
+fn solve(input: &str, rules: Rules) -> Option<usize> {
+    let solver = parse_input(input);
+    let target = Point {
+        x: solver.grid[0].len() as isize - 1,
+        y: solver.grid.len() as isize - 1,
+    };
+    let route = solver.route(rules, Point { x: 0, y: 0 }, target)?;
+    if flag("--print") {
+        println!("{}", solver.render(&route));
+    }
+    Some(route.heat_loss)
 }
 
 fn parse_input(input: &str) -> CrucibleSolver {
@@ -66,138 +105,113 @@ fn parse_input(input: &str) -> CrucibleSolver {
     CrucibleSolver { grid }
 }
 
-impl Direction {
-    fn turn_left(&self) -> Direction {
-        match self {
-            Self::Up => Self::Left,
-            Self::Down => Self::Right,
-            Self::Left => Self::Down,
-            Self::Right => Self::Up,
-        }
-    }
-    fn turn_right(&self) -> Direction {
-        match self {
-            Self::Up => Self::Right,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up,
-            Self::Right => Self::Down,
+impl Crucible {
+    fn step(&self, direction: Direction, momentum: u8) -> Crucible {
+        Crucible {
+            position: self.position.moved(direction, 1),
+            direction,
+            momentum,
         }
     }
 }
 
-impl Crucible {
-    fn step(&mut self) -> () {
-        let direction = self.direction.clone();
-
-        let (x, y) = if let Some(p) = &self.position {
-            match direction {
-                Direction::Up => (p.x, p.y - 1),
-                Direction::Down => (p.x, p.y + 1),
-                Direction::Left => (p.x - 1, p.y),
-                Direction::Right => (p.x + 1, p.y),
-            }
-        } else {
-            self.position = None;
-            return;
-        };
-
-        self.position = Some(Point { x, y })
+impl CrucibleSolver {
+    fn in_bounds(&self, point: Point) -> bool {
+        (0..self.grid.len() as isize).contains(&point.y)
+            && (0..self.grid[0].len() as isize).contains(&point.x)
     }
-}
 
-impl CrucibleSolver {
-    fn heat_loss(&self, min_momentum: u8, max_momentun: u8) -> usize {
-        // BFS Search for minimum heat loss
-        let rows = self.grid.len();
-        let cols = self.grid[0].len();
-        let target = Point {
-            x: cols as isize - 1,
-            y: rows as isize - 1,
-        };
+    fn heat_loss_at(&self, point: Point) -> usize {
+        self.grid[point.y as usize][point.x as usize]
+    }
 
-        let mut heap = BinaryHeap::new();
-        let mut cost_map = std::collections::HashMap::new();
+    /// The cheapest way from `start` to `target` under `rules`, not counting the start block.
+    fn route(&self, rules: Rules, start: Point, target: Point) -> Option<Route> {
+        if !self.in_bounds(start) || !self.in_bounds(target) {
+            return None;
+        }
 
-        // Start top left, going down and right
-        heap.push(Crucible {
-            position: Some(Point { x: 0, y: 0 }),
-            direction: Direction::Down,
-            momentum: 0,
-            heat_loss: 0,
-        });
-        heap.push(Crucible {
-            position: Some(Point { x: 0, y: 0 }),
-            direction: Direction::Right,
+        // The crucible hasn't moved yet, so it can set off any way
+        let starts = [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .map(|direction| Crucible {
+            position: start,
+            direction,
             momentum: 0,
-            heat_loss: 0,
         });
 
-        // Essentially Dijkstra's
-        while let Some(crucible) = heap.pop() {
-            if let Some(p) = crucible.position {
-                // If at final and able to stop, needed for part two
-                if p.x == target.x && p.y == target.y && crucible.momentum >= min_momentum {
-                    return crucible.heat_loss;
-                }
-
-                let state_key = (p.x, p.y, crucible.direction.clone(), crucible.momentum);
-                if let Some(&new_heat_loss) = cost_map.get(&state_key) {
-                    if crucible.heat_loss > new_heat_loss {
-                        continue;
-                    }
-                }
-
-                let possible_moves = [
-                    (crucible.direction.clone(), crucible.momentum + 1),
-                    (crucible.direction.turn_left(), 1),
-                    (crucible.direction.turn_right(), 1),
-                ];
-
-                for (new_direction, new_momentum) in possible_moves.iter() {
-                    if *new_momentum > max_momentun {
-                        continue;
-                    }
-
-                    if crucible.momentum < min_momentum && *new_direction != crucible.direction {
-                        continue;
-                    }
-
-                    let mut new_crucible = Crucible {
-                        position: crucible.position,
-                        direction: new_direction.clone(),
-                        momentum: *new_momentum,
-                        heat_loss: crucible.heat_loss,
-                    };
-                    new_crucible.step();
-
-                    if let Some(new_position) = new_crucible.position {
-                        if new_position.x < 0
-                            || new_position.x >= cols as isize
-                            || new_position.y < 0
-                            || new_position.y >= rows as isize
-                        {
-                            continue;
-                        }
-
-                        new_crucible.heat_loss +=
-                            self.grid[new_position.y as usize][new_position.x as usize];
-                        let new_state = (
-                            new_position.x,
-                            new_position.y,
-                            new_direction.clone(),
-                            *new_momentum,
-                        );
-                        if new_crucible.heat_loss < *cost_map.get(&new_state).unwrap_or(&usize::MAX)
-                        {
-                            cost_map.insert(new_state, new_crucible.heat_loss);
-                            heap.push(new_crucible);
-                        }
-                    }
-                }
-            }
-        }
+        let successors = |crucible: &Crucible| {
+            let straight = (crucible.direction, crucible.momentum + 1);
+            let back = rules.reverse.then(|| crucible.direction.opposite());
+            let turns = rules
+                .turns
+                .iter()
+                .map(|turn| turn.apply(crucible.direction))
+                .chain(back)
+                .filter(|_| crucible.momentum >= rules.min_run)
+                .map(|direction| (direction, 1));
+
+            std::iter::once(straight)
+                .chain(turns)
+                .filter(|&(_, momentum)| momentum <= rules.max_run)
+                .map(|(direction, momentum)| crucible.step(direction, momentum))
+                .filter(|next| self.in_bounds(next.position))
+                .map(|next| {
+                    let heat_loss = self.heat_loss_at(next.position);
+                    (next, heat_loss)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The ultra crucible can't stop until it's gone far enough
+        let at_target =
+            |crucible: &Crucible| crucible.position == target && crucible.momentum >= rules.min_run;
+
+        let result = dijkstra(starts, successors, at_target)?;
+        let steps = result.path[1..]
+            .iter()
+            .map(|crucible| Step {
+                position: crucible.position,
+                direction: crucible.direction,
+                heat_loss: self.heat_loss_at(crucible.position),
+            })
+            .collect();
+
+        Some(Route {
+            heat_loss: result.cost,
+            steps,
+        })
+    }
 
-        usize::max_value()
+    /// The grid with an arrow on every block the route moves into, like the puzzle's diagrams.
+    ///
+    /// Where a route crosses itself the last arrow wins.
+    fn render(&self, route: &Route) -> String {
+        let mut output: Vec<Vec<char>> = self
+            .grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&heat_loss| char::from_digit(heat_loss as u32, 10).unwrap())
+                    .collect()
+            })
+            .collect();
+        for step in &route.steps {
+            output[step.position.y as usize][step.position.x as usize] = match step.direction {
+                Direction::Up => '^',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                Direction::Right => '>',
+            };
+        }
+        output
+            .into_iter()
+            .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+            .collect()
     }
 }
 
@@ -229,4 +243,83 @@ mod tests {
         assert_eq!(result.grid.len(), 13);
         assert_eq!(result.grid[0].len(), 13);
     }
+
+    #[test]
+    fn test_route() {
+        let solver = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let target = Point { x: 12, y: 12 };
+        let route = solver
+            .route(CRUCIBLE, Point { x: 0, y: 0 }, target)
+            .unwrap();
+        assert_eq!(route.heat_loss, 102);
+        assert_eq!(route.steps.iter().map(|s| s.heat_loss).sum::<usize>(), 102);
+        assert_eq!(route.steps.last().unwrap().position, target);
+        assert!(route
+            .steps
+            .windows(2)
+            .all(|w| w[0].position.moved(w[1].direction, 1) == w[1].position));
+
+        let rendered = solver.render(&route);
+        assert_eq!(rendered.lines().count(), 13);
+        assert_eq!(
+            rendered.chars().filter(|c| "^v<>".contains(*c)).count(),
+            route.steps.len()
+        );
+
+        // Backwards from the bottom right
+        let back = solver
+            .route(CRUCIBLE, target, Point { x: 0, y: 0 })
+            .unwrap();
+        assert_eq!(back.steps.last().unwrap().position, Point { x: 0, y: 0 });
+        assert_eq!(solver.route(CRUCIBLE, Point { x: 13, y: 0 }, target), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let solver = parse_input("123\n456\n789");
+        let route = solver
+            .route(CRUCIBLE, Point { x: 0, y: 0 }, Point { x: 2, y: 2 })
+            .unwrap();
+        assert_eq!(route.heat_loss, 2 + 3 + 6 + 9);
+        assert_eq!(solver.render(&route), "1>>\n45v\n78v\n");
+    }
+
+    #[test]
+    fn test_turning_rules() {
+        // Has to go two blocks before stopping, which can only be done by overshooting and
+        // coming back
+        let solver = parse_input("11111");
+        let start = Point { x: 0, y: 0 };
+        let target = Point { x: 1, y: 0 };
+        let rules = Rules {
+            min_run: 2,
+            max_run: 3,
+            turns: &[Turn::Left, Turn::Right],
+            reverse: false,
+        };
+        assert_eq!(solver.route(rules, start, target), None);
+
+        let reversing = Rules {
+            reverse: true,
+            ..rules
+        };
+        let route = solver.route(reversing, start, target).unwrap();
+        assert_eq!(route.heat_loss, 5);
+        assert_eq!(route.steps[2].direction, Direction::Right);
+        assert_eq!(route.steps[3].direction, Direction::Left);
+
+        // Only ever turning left still gets there, just not as cheaply
+        let solver = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let left_only = Rules {
+            turns: &[Turn::Left],
+            ..CRUCIBLE
+        };
+        let route = solver
+            .route(left_only, Point { x: 0, y: 0 }, Point { x: 12, y: 12 })
+            .unwrap();
+        assert_eq!(route.heat_loss, 239);
+        assert!(route.steps.windows(2).all(|w| {
+            w[1].direction == w[0].direction || w[1].direction == w[0].direction.turn_left()
+        }));
+    }
 }
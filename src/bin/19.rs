@@ -0,0 +1,272 @@
+use std::{collections::HashMap, ops::Range};
+
+use advent_of_code::{
+    interval::split,
+    parse::{lines, parse_all, unsigned, word, IResult},
+};
+use nom::{
+    branch::alt,
+    character::complete::{alpha1, char, line_ending},
+    multi::separated_list1,
+    sequence::{delimited, pair, separated_pair, tuple},
+    Parser,
+};
+use nom_supreme::{tag::complete::tag, ParserExt};
+
+advent_of_code::solution!(19);
+
+pub fn part_one(input: &str) -> Option<u64> {
+    let (workflows, parts) = parse_input(input);
+    let result = parts
+        .iter()
+        .filter(|part| workflows.accepts(part))
+        .map(|part| part.rating())
+        .sum();
+    Some(result)
+}
+
+pub fn part_two(input: &str) -> Option<u64> {
+    let (workflows, _) = parse_input(input);
+    let all = std::array::from_fn(|_| 1..4001);
+    Some(workflows.combinations("in", all))
+}
+
+/// Ratings for the `x`, `m`, `a` and `s` categories, in that order.
+#[derive(Debug, PartialEq, Eq)]
+struct Part([u64; 4]);
+
+/// A range of ratings per category, every combination of which takes the same path.
+type PartRanges = [Range<u64>; 4];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target<'a> {
+    Accept,
+    Reject,
+    Workflow(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    Greater,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Condition {
+    category: usize,
+    comparison: Comparison,
+    value: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Rule<'a> {
+    condition: Option<Condition>,
+    target: Target<'a>,
+}
+
+#[derive(Debug)]
+struct Workflows<'a>(HashMap<&'a str, Vec<Rule<'a>>>);
+
+impl Part {
+    fn rating(&self) -> u64 {
+        self.0.iter().sum()
+    }
+}
+
+impl Condition {
+    fn matches(&self, part: &Part) -> bool {
+        let rating = part.0[self.category];
+        match self.comparison {
+            Comparison::Less => rating < self.value,
+            Comparison::Greater => rating > self.value,
+        }
+    }
+
+    /// Splits a range of ratings into the part that meets the condition and the part that doesn't.
+    fn split(&self, range: &Range<u64>) -> (Option<Range<u64>>, Option<Range<u64>>) {
+        match self.comparison {
+            Comparison::Less => split(range, self.value),
+            Comparison::Greater => {
+                let (below, above) = split(range, self.value + 1);
+                (above, below)
+            }
+        }
+    }
+}
+
+impl<'a> Workflows<'a> {
+    fn accepts(&self, part: &Part) -> bool {
+        let mut current = "in";
+        loop {
+            let rule = self.0[current]
+                .iter()
+                .find(|rule| rule.condition.as_ref().is_none_or(|c| c.matches(part)))
+                .expect("the last rule of a workflow has no condition");
+            match rule.target {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(next) => current = next,
+            }
+        }
+    }
+
+    /// Number of rating combinations within `ranges` that end up accepted, starting at `workflow`.
+    fn combinations(&self, workflow: &str, mut ranges: PartRanges) -> u64 {
+        let mut total = 0;
+        for rule in &self.0[workflow] {
+            let matching = match &rule.condition {
+                None => Some(std::mem::replace(
+                    &mut ranges,
+                    std::array::from_fn(|_| 0..0),
+                )),
+                Some(condition) => {
+                    let (hit, miss) = condition.split(&ranges[condition.category]);
+                    hit.map(|hit| {
+                        let mut matching = ranges.clone();
+                        matching[condition.category] = hit;
+                        ranges[condition.category] = miss.unwrap_or(0..0);
+                        matching
+                    })
+                }
+            };
+            if let Some(matching) = matching {
+                total += match rule.target {
+                    Target::Accept => matching.iter().map(|r| r.end - r.start).product(),
+                    Target::Reject => 0,
+                    Target::Workflow(next) => self.combinations(next, matching),
+                };
+            }
+            if ranges.iter().any(|r| r.is_empty()) {
+                break;
+            }
+        }
+        total
+    }
+}
+
+fn parse_input(input: &str) -> (Workflows<'_>, Vec<Part>) {
+    let (workflows, parts) = parse_all(
+        separated_pair(
+            lines(parse_workflow),
+            pair(line_ending, line_ending),
+            lines(parse_part),
+        ),
+        input,
+    )
+    .unwrap();
+    (Workflows(workflows.into_iter().collect()), parts)
+}
+
+fn parse_target(input: &str) -> IResult<'_, Target<'_>> {
+    alpha1
+        .map(|name| match name {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            name => Target::Workflow(name),
+        })
+        .parse(input)
+}
+
+fn parse_category(input: &str) -> IResult<'_, usize> {
+    word(&[("x", 0), ("m", 1), ("a", 2), ("s", 3)])(input)
+}
+
+/// Parses a rule like `a<2006:qkq`, or a bare fallback target like `rfg`.
+fn parse_rule(input: &str) -> IResult<'_, Rule<'_>> {
+    let comparison = word(&[("<", Comparison::Less), (">", Comparison::Greater)]);
+    let condition =
+        tuple((parse_category, comparison, unsigned)).map(|(category, comparison, value)| {
+            Condition {
+                category,
+                comparison,
+                value,
+            }
+        });
+
+    alt((
+        separated_pair(condition, char(':'), parse_target).map(|(condition, target)| Rule {
+            condition: Some(condition),
+            target,
+        }),
+        parse_target.map(|target| Rule {
+            condition: None,
+            target,
+        }),
+    ))
+    .context("rule")
+    .parse(input)
+}
+
+/// Parses a workflow like `px{a<2006:qkq,m>2090:A,rfg}`.
+fn parse_workflow(input: &str) -> IResult<'_, (&str, Vec<Rule<'_>>)> {
+    pair(
+        alpha1,
+        delimited(char('{'), separated_list1(char(','), parse_rule), char('}')),
+    )
+    .context("workflow")
+    .parse(input)
+}
+
+/// Parses a part like `{x=787,m=2655,a=1222,s=2876}`.
+fn parse_part(input: &str) -> IResult<'_, Part> {
+    tuple((
+        unsigned.preceded_by(tag("{x=")),
+        unsigned.preceded_by(tag(",m=")),
+        unsigned.preceded_by(tag(",a=")),
+        unsigned.preceded_by(tag(",s=")).terminated(char('}')),
+    ))
+    .map(|(x, m, a, s)| Part([x, m, a, s]))
+    .context("part")
+    .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(19114));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(167409079868000));
+    }
+
+    #[test]
+    fn test_parse_workflow() {
+        let (rest, (name, rules)) = parse_workflow("px{a<2006:qkq,m>2090:A,rfg}").unwrap();
+        assert_eq!((rest, name, rules.len()), ("", "px", 3));
+        assert_eq!(
+            rules[1],
+            Rule {
+                condition: Some(Condition {
+                    category: 1,
+                    comparison: Comparison::Greater,
+                    value: 2090,
+                }),
+                target: Target::Accept,
+            }
+        );
+        assert_eq!(rules[2].target, Target::Workflow("rfg"));
+    }
+
+    #[test]
+    fn test_condition_split() {
+        let less = Condition {
+            category: 0,
+            comparison: Comparison::Less,
+            value: 10,
+        };
+        assert_eq!(less.split(&(1..4001)), (Some(1..10), Some(10..4001)));
+        let greater = Condition {
+            comparison: Comparison::Greater,
+            ..less
+        };
+        assert_eq!(greater.split(&(1..4001)), (Some(11..4001), Some(1..11)));
+        assert_eq!(greater.split(&(1..5)), (None, Some(1..5)));
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{HashMap, VecDeque};
+
+use advent_of_code::{
+    flag,
+    parse::{lines, parse_all, IResult},
+};
+use nom::{
+    branch::alt,
+    character::complete::{alpha1, char},
+    multi::separated_list1,
+    sequence::separated_pair,
+    Parser,
+};
+use nom_supreme::{tag::complete::tag, ParserExt};
+use num::integer::lcm;
+
+advent_of_code::solution!(20);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let mut network = parse_input(input);
+    let trace = flag("--trace");
+    let (mut low, mut high) = (0, 0);
+    for _ in 0..1000 {
+        network.press(|pulse| {
+            if trace {
+                println!("{pulse}");
+            }
+            if pulse.high {
+                high += 1;
+            } else {
+                low += 1;
+            }
+        });
+    }
+    Some(low * high)
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let mut network = parse_input(input);
+    network.presses_until_low("rx")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ModuleKind<'a> {
+    Broadcaster,
+    /// On or off.
+    FlipFlop(bool),
+    /// The most recent pulse from each input, high or not.
+    Conjunction(HashMap<&'a str, bool>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Module<'a> {
+    kind: ModuleKind<'a>,
+    outputs: Vec<&'a str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pulse<'a> {
+    from: &'a str,
+    to: &'a str,
+    high: bool,
+}
+
+#[derive(Debug)]
+struct Network<'a> {
+    modules: HashMap<&'a str, Module<'a>>,
+}
+
+impl std::fmt::Display for Pulse<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = if self.high { "high" } else { "low" };
+        write!(f, "{} -{level}-> {}", self.from, self.to)
+    }
+}
+
+impl<'a> Module<'a> {
+    /// Handles a pulse, returning whether to send a high pulse to every output, if anything is sent.
+    fn receive(&mut self, pulse: &Pulse<'a>) -> Option<bool> {
+        match &mut self.kind {
+            ModuleKind::Broadcaster => Some(pulse.high),
+            ModuleKind::FlipFlop(_) if pulse.high => None,
+            ModuleKind::FlipFlop(on) => {
+                *on = !*on;
+                Some(*on)
+            }
+            ModuleKind::Conjunction(memory) => {
+                memory.insert(pulse.from, pulse.high);
+                Some(!memory.values().all(|&high| high))
+            }
+        }
+    }
+}
+
+impl<'a> Network<'a> {
+    fn new(modules: Vec<(&'a str, Module<'a>)>) -> Self {
+        let mut modules: HashMap<&str, Module> = modules.into_iter().collect();
+
+        // Conjunctions start out remembering a low pulse from each of their inputs
+        let connections: Vec<(&str, &str)> = modules
+            .iter()
+            .flat_map(|(&name, module)| module.outputs.iter().map(move |&to| (name, to)))
+            .collect();
+        for (from, to) in connections {
+            if let Some(Module {
+                kind: ModuleKind::Conjunction(memory),
+                ..
+            }) = modules.get_mut(to)
+            {
+                memory.insert(from, false);
+            }
+        }
+
+        Network { modules }
+    }
+
+    /// Names of the modules sending to `name`.
+    fn inputs(&self, name: &str) -> Vec<&'a str> {
+        self.modules
+            .iter()
+            .filter(|(_, module)| module.outputs.contains(&name))
+            .map(|(&from, _)| from)
+            .collect()
+    }
+
+    /// Pushes the button once, calling `on_pulse` for every pulse in the order they are processed.
+    fn press(&mut self, mut on_pulse: impl FnMut(&Pulse<'a>)) {
+        let mut queue = VecDeque::from([Pulse {
+            from: "button",
+            to: "broadcaster",
+            high: false,
+        }]);
+
+        while let Some(pulse) = queue.pop_front() {
+            on_pulse(&pulse);
+            let Some(module) = self.modules.get_mut(pulse.to) else {
+                continue;
+            };
+            if let Some(high) = module.receive(&pulse) {
+                queue.extend(module.outputs.iter().map(|&to| Pulse {
+                    from: pulse.to,
+                    to,
+                    high,
+                }));
+            }
+        }
+    }
+
+    /// Button presses until `target` first receives a low pulse.
+    ///
+    /// Expects `target` to be fed by a single conjunction, whose inputs each send it a high pulse
+    /// on a fixed cycle of presses. The target fires once all of them line up.
+    fn presses_until_low(&mut self, target: &str) -> Option<usize> {
+        let [hub] = self.inputs(target)[..] else {
+            return None;
+        };
+        let mut cycles: HashMap<&str, Option<usize>> = self
+            .inputs(hub)
+            .into_iter()
+            .map(|name| (name, None))
+            .collect();
+
+        for presses in 1.. {
+            self.press(|pulse| {
+                if pulse.to == hub && pulse.high {
+                    cycles.entry(pulse.from).and_modify(|c| {
+                        c.get_or_insert(presses);
+                    });
+                }
+            });
+            if cycles.values().all(Option::is_some) {
+                break;
+            }
+        }
+        Some(cycles.into_values().flatten().fold(1, lcm))
+    }
+}
+
+fn parse_input(input: &str) -> Network<'_> {
+    Network::new(parse_all(lines(parse_module), input).unwrap())
+}
+
+/// Parses a line like `%a -> inv, con`.
+fn parse_module(input: &str) -> IResult<'_, (&str, Module<'_>)> {
+    let kind = alt((
+        tag("broadcaster").map(|name| (name, ModuleKind::Broadcaster)),
+        alpha1
+            .preceded_by(char('%'))
+            .map(|name| (name, ModuleKind::FlipFlop(false))),
+        alpha1
+            .preceded_by(char('&'))
+            .map(|name| (name, ModuleKind::Conjunction(HashMap::new()))),
+    ));
+
+    separated_pair(kind, tag(" -> "), separated_list1(tag(", "), alpha1))
+        .map(|((name, kind), outputs)| (name, Module { kind, outputs }))
+        .context("module")
+        .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(11687500));
+    }
+
+    #[test]
+    fn test_part_one_simple() {
+        let result = part_one(
+            "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a",
+        );
+        assert_eq!(result, Some(32000000));
+    }
+
+    #[test]
+    fn test_part_two() {
+        // `fa` fires high every 2nd press and `fb` every 4th, so `rx` first gets a low on press 4
+        let result = part_two(
+            "broadcaster -> a
+%a -> b, fa
+%b -> fb
+&fa -> hub
+&fb -> hub
+&hub -> rx",
+        );
+        assert_eq!(result, Some(4));
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut network = parse_input(&input);
+        let mut trace = vec![];
+        network.press(|pulse| trace.push(pulse.to_string()));
+        assert_eq!(
+            trace,
+            vec![
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -high-> inv",
+                "a -high-> con",
+                "inv -low-> b",
+                "con -high-> output",
+                "b -high-> con",
+                "con -low-> output",
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{HashMap, VecDeque};
+
+use advent_of_code::{
+    parse::{grid, parse_all},
+    sequence::extrapolate_nth,
+    Direction, Point,
+};
+
+advent_of_code::solution!(21);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let garden = parse_input(input);
+    Some(garden.reachable_plots(64))
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let garden = parse_input(input);
+    Some(garden.extrapolated_plots(26501365))
+}
+
+#[derive(Debug)]
+struct Garden {
+    rocks: Vec<Vec<bool>>,
+    width: usize,
+    height: usize,
+    start: Point,
+}
+
+impl Garden {
+    fn is_rock(&self, point: &Point) -> bool {
+        let x = point.x.rem_euclid(self.width as isize) as usize;
+        let y = point.y.rem_euclid(self.height as isize) as usize;
+        self.rocks[y][x]
+    }
+
+    /// Fewest steps to every plot within `max_steps` of the start.
+    ///
+    /// With `tiled` the garden repeats forever in every direction, otherwise its edges are walls.
+    fn distances(&self, max_steps: usize, tiled: bool) -> HashMap<Point, usize> {
+        let in_bounds = |p: &Point| {
+            tiled
+                || (0..self.width as isize).contains(&p.x)
+                    && (0..self.height as isize).contains(&p.y)
+        };
+        let mut distances = HashMap::from([(self.start, 0)]);
+        let mut queue = VecDeque::from([self.start]);
+
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[&point];
+            if distance == max_steps {
+                continue;
+            }
+            for direction in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                let next = point.moved(direction, 1);
+                if in_bounds(&next) && !self.is_rock(&next) && !distances.contains_key(&next) {
+                    distances.insert(next, distance + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Plots that can be ended on after exactly `steps` steps, for each of the given step counts.
+    ///
+    /// A plot reached in fewer steps can still be ended on by stepping back and forth, as long as
+    /// the leftover steps are even.
+    fn count_plots(&self, steps: &[usize], tiled: bool) -> Vec<usize> {
+        let max_steps = steps.iter().copied().max().unwrap_or(0);
+        let distances = self.distances(max_steps, tiled);
+        steps
+            .iter()
+            .map(|&s| {
+                distances
+                    .values()
+                    .filter(|&&d| d <= s && d % 2 == s % 2)
+                    .count()
+            })
+            .collect()
+    }
+
+    fn reachable_plots(&self, steps: usize) -> usize {
+        self.count_plots(&[steps], false)[0]
+    }
+
+    /// Plots reachable after `steps` steps in the tiled garden, for step counts too large to walk.
+    ///
+    /// Relies on the start row and column being clear, so that the reachable diamond grows by one
+    /// garden in each direction every `width` steps. Sampled at those step counts the number of
+    /// plots is quadratic, so three samples are enough to extrapolate the rest.
+    fn extrapolated_plots(&self, steps: usize) -> usize {
+        assert_eq!(
+            self.width, self.height,
+            "the garden has to be square to grow by whole gardens"
+        );
+        let size = self.width;
+        let samples: Vec<usize> = (0..3).map(|k| steps % size + k * size).collect();
+        // The differences between samples can be negative
+        let counts: Vec<i64> = self
+            .count_plots(&samples, true)
+            .into_iter()
+            .map(|count| count as i64)
+            .collect();
+        extrapolate_nth(&counts, steps / size) as usize
+    }
+}
+
+fn parse_input(input: &str) -> Garden {
+    let cells = parse_all(grid, input).unwrap();
+    let mut start = Point { x: 0, y: 0 };
+    let rocks = cells
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, &c)| {
+                    if c == 'S' {
+                        start = Point {
+                            x: x as isize,
+                            y: y as isize,
+                        };
+                    }
+                    c == '#'
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<bool>>>();
+
+    Garden {
+        width: rocks[0].len(),
+        height: rocks.len(),
+        rocks,
+        start,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachable_plots() {
+        let garden = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(garden.reachable_plots(1), 2);
+        assert_eq!(garden.reachable_plots(2), 4);
+        assert_eq!(garden.reachable_plots(6), 16);
+    }
+
+    #[test]
+    fn test_reachable_plots_tiled() {
+        let garden = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let result = garden.count_plots(&[6, 10, 50, 100], true);
+        assert_eq!(result, vec![16, 50, 1594, 6536]);
+    }
+
+    #[test]
+    fn test_extrapolated_plots() {
+        // The example lacks the clear start row and column the real inputs have, so use a garden
+        // that has them
+        let garden = parse_input(
+            ".......
+.#...#.
+....#..
+...S...
+.#.....
+.....#.
+.......",
+        );
+        for steps in [17, 38, 52] {
+            assert_eq!(
+                garden.extrapolated_plots(steps),
+                garden.count_plots(&[steps], true)[0]
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "square")]
+    fn test_extrapolated_plots_not_square() {
+        let garden = parse_input(".....\n..S..\n.....");
+        garden.extrapolated_plots(100);
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let garden = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!((garden.width, garden.height), (11, 11));
+        assert_eq!(garden.start, Point { x: 5, y: 5 });
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use advent_of_code::{
+    parse::{lines, parse_all, unsigned, IResult},
+    Point3,
+};
+use nom::{character::complete::char, sequence::separated_pair, Parser};
+use nom_supreme::ParserExt;
+
+advent_of_code::solution!(22);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let stack = Stack::settle(parse_input(input));
+    let result = (0..stack.bricks.len())
+        .filter(|&i| stack.can_disintegrate(i))
+        .count();
+    Some(result)
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let stack = Stack::settle(parse_input(input));
+    let result = (0..stack.bricks.len())
+        .map(|i| stack.chain_reaction(i))
+        .sum();
+    Some(result)
+}
+
+/// A line of cubes between two corners, inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Brick {
+    start: Point3,
+    end: Point3,
+}
+
+/// Bricks after they've all fallen, ordered from the ground up, with which rest on which.
+#[derive(Debug)]
+struct Stack {
+    bricks: Vec<Brick>,
+    /// Bricks resting directly on top of each brick.
+    supports: Vec<Vec<usize>>,
+    /// Bricks each brick rests directly on top of.
+    supported_by: Vec<Vec<usize>>,
+}
+
+impl Brick {
+    fn new(a: Point3, b: Point3) -> Self {
+        Brick {
+            start: Point3 {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+                z: a.z.min(b.z),
+            },
+            end: Point3 {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+                z: a.z.max(b.z),
+            },
+        }
+    }
+
+    /// The `(x, y)` columns the brick covers.
+    fn footprint(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        (self.start.x..=self.end.x)
+            .flat_map(move |x| (self.start.y..=self.end.y).map(move |y| (x, y)))
+    }
+
+    fn dropped_to(&self, z: isize) -> Brick {
+        let height = self.end.z - self.start.z;
+        Brick {
+            start: Point3 { z, ..self.start },
+            end: Point3 {
+                z: z + height,
+                ..self.end
+            },
+        }
+    }
+}
+
+impl Stack {
+    /// Lets every brick fall as far as it can, lowest first.
+    fn settle(mut bricks: Vec<Brick>) -> Self {
+        bricks.sort_unstable_by_key(|b| b.start.z);
+
+        // The top of each column so far, and which brick it belongs to
+        let mut heights: HashMap<(isize, isize), (isize, usize)> = HashMap::new();
+        let mut settled = Vec::with_capacity(bricks.len());
+        let mut supports = vec![vec![]; bricks.len()];
+        let mut supported_by = vec![vec![]; bricks.len()];
+
+        for (i, brick) in bricks.iter().enumerate() {
+            let below: Vec<(isize, usize)> = brick
+                .footprint()
+                .filter_map(|column| heights.get(&column).copied())
+                .collect();
+            let top = below.iter().map(|&(z, _)| z).max().unwrap_or(0);
+
+            for &(z, j) in &below {
+                if z == top && !supported_by[i].contains(&j) {
+                    supported_by[i].push(j);
+                    supports[j].push(i);
+                }
+            }
+
+            let brick = brick.dropped_to(top + 1);
+            for column in brick.footprint() {
+                heights.insert(column, (brick.end.z, i));
+            }
+            settled.push(brick);
+        }
+
+        Stack {
+            bricks: settled,
+            supports,
+            supported_by,
+        }
+    }
+
+    /// Whether removing the brick leaves everything it holds up still resting on something else.
+    fn can_disintegrate(&self, brick: usize) -> bool {
+        self.supports[brick]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// Number of other bricks that fall when this one is removed.
+    fn chain_reaction(&self, brick: usize) -> usize {
+        let mut falling = vec![false; self.bricks.len()];
+        falling[brick] = true;
+
+        // Bricks are in settled order, so every brick comes after all the ones holding it up
+        let mut count = 0;
+        for above in brick + 1..self.bricks.len() {
+            let holders = &self.supported_by[above];
+            if !holders.is_empty() && holders.iter().all(|&b| falling[b]) {
+                falling[above] = true;
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+fn parse_input(input: &str) -> Vec<Brick> {
+    parse_all(lines(parse_brick), input).unwrap()
+}
+
+fn parse_point(input: &str) -> IResult<'_, Point3> {
+    separated_pair(
+        separated_pair(unsigned, char(','), unsigned),
+        char(','),
+        unsigned,
+    )
+    .map(|((x, y), z)| Point3 { x, y, z })
+    .parse(input)
+}
+
+/// Parses a line like `1,0,1~1,2,1`.
+fn parse_brick(input: &str) -> IResult<'_, Brick> {
+    separated_pair(parse_point, char('~'), parse_point)
+        .map(|(a, b)| Brick::new(a, b))
+        .context("brick")
+        .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(5));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(7));
+    }
+
+    #[test]
+    fn test_settle() {
+        let stack = Stack::settle(parse_input(&advent_of_code::template::read_file(
+            "examples", DAY,
+        )));
+        let bottoms: Vec<isize> = stack.bricks.iter().map(|b| b.start.z).collect();
+        assert_eq!(bottoms, vec![1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(stack.supports[0], vec![1, 2]);
+        assert_eq!(stack.supported_by[3], vec![1, 2]);
+        assert_eq!(stack.chain_reaction(0), 6);
+        assert_eq!(stack.chain_reaction(5), 1);
+    }
+
+    #[test]
+    fn test_parse_brick() {
+        let (_, brick) = parse_brick("2,2,2~2,2,1").unwrap();
+        assert_eq!(brick.start, Point3 { x: 2, y: 2, z: 1 });
+        assert_eq!(brick.end, Point3 { x: 2, y: 2, z: 2 });
+    }
+}
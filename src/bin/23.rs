@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use advent_of_code::{
+    parse::{grid, parse_all},
+    Direction, Point,
+};
+use rayon::prelude::*;
+
+advent_of_code::solution!(23);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let trails = parse_input(input);
+    trails.junction_graph(true).longest_hike()
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let trails = parse_input(input);
+    trails.junction_graph(false).longest_hike()
+}
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+#[derive(Debug)]
+struct Trails {
+    tiles: Vec<Vec<char>>,
+    width: usize,
+    height: usize,
+    start: Point,
+    end: Point,
+}
+
+/// The trails boiled down to the junctions where a choice has to be made, with the length of the
+/// corridors between them. Junction 0 is the start and junction 1 the end.
+#[derive(Debug)]
+struct Graph {
+    edges: Vec<Vec<(usize, usize)>>,
+}
+
+/// A hike in progress: where it is, the junctions it has been through and how far it has come.
+#[derive(Debug, Clone, Copy)]
+struct Hike {
+    junction: usize,
+    visited: u64,
+    length: usize,
+}
+
+impl Trails {
+    fn tile(&self, point: &Point) -> Option<char> {
+        if point.x < 0 || point.y < 0 {
+            return None;
+        }
+        self.tiles
+            .get(point.y as usize)
+            .and_then(|row| row.get(point.x as usize))
+            .copied()
+            .filter(|&c| c != '#')
+    }
+
+    /// Whether the tile at `point` can be stepped onto going `direction`.
+    fn can_enter(&self, point: &Point, direction: Direction, slippery: bool) -> bool {
+        match self.tile(point) {
+            None => false,
+            Some('^') if slippery => direction == Direction::Up,
+            Some('v') if slippery => direction == Direction::Down,
+            Some('<') if slippery => direction == Direction::Left,
+            Some('>') if slippery => direction == Direction::Right,
+            Some(_) => true,
+        }
+    }
+
+    fn junctions(&self) -> Vec<Point> {
+        let mut junctions = vec![self.start, self.end];
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let point = Point {
+                    x: x as isize,
+                    y: y as isize,
+                };
+                let exits = DIRECTIONS
+                    .iter()
+                    .filter(|&&d| self.tile(&point.moved(d, 1)).is_some())
+                    .count();
+                if self.tile(&point).is_some() && exits > 2 {
+                    junctions.push(point);
+                }
+            }
+        }
+        junctions
+    }
+
+    /// Follows every corridor out of every junction to the next junction it reaches.
+    ///
+    /// With `slippery` set, slopes can only be walked down, which makes some corridors one way.
+    fn junction_graph(&self, slippery: bool) -> Graph {
+        let junctions = self.junctions();
+        let index: HashMap<Point, usize> =
+            junctions.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+
+        let edges = junctions
+            .iter()
+            .map(|&from| {
+                DIRECTIONS
+                    .iter()
+                    .filter_map(|&first| {
+                        let mut point = from.moved(first, 1);
+                        if !self.can_enter(&point, first, slippery) {
+                            return None;
+                        }
+                        let mut direction = first;
+                        let mut length = 1;
+                        while !index.contains_key(&point) {
+                            direction = [direction, direction.turn_left(), direction.turn_right()]
+                                .into_iter()
+                                .find(|&d| self.can_enter(&point.moved(d, 1), d, slippery))?;
+                            point = point.moved(direction, 1);
+                            length += 1;
+                        }
+                        Some((index[&point], length))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Graph { edges }
+    }
+}
+
+impl Graph {
+    /// Hikes one step further, to every junction not visited yet.
+    fn extend(&self, hike: Hike) -> impl Iterator<Item = Hike> + '_ {
+        // Once next to the end, going anywhere else would cut it off
+        let to_end = self.edges[hike.junction].iter().find(|&&(to, _)| to == 1);
+        self.edges[hike.junction]
+            .iter()
+            .filter(move |&&(to, _)| to_end.is_none() || to == 1)
+            .filter(move |&&(to, _)| hike.visited & (1 << to) == 0)
+            .map(move |&(to, length)| Hike {
+                junction: to,
+                visited: hike.visited | (1 << to),
+                length: hike.length + length,
+            })
+    }
+
+    /// Length of the longest hike from `hike` to the end, by trying every path.
+    fn longest_from(&self, hike: Hike) -> Option<usize> {
+        if hike.junction == 1 {
+            return Some(hike.length);
+        }
+        self.extend(hike)
+            .filter_map(|next| self.longest_from(next))
+            .max()
+    }
+
+    /// Length of the longest hike from start to end that never visits a junction twice.
+    ///
+    /// The first few junctions are explored up front so that the searches from there can be
+    /// split across threads.
+    fn longest_hike(&self) -> Option<usize> {
+        assert!(self.edges.len() <= 64, "too many junctions for the bitset");
+
+        let mut hikes = vec![Hike {
+            junction: 0,
+            visited: 1,
+            length: 0,
+        }];
+        for _ in 0..6 {
+            hikes = hikes
+                .into_iter()
+                .flat_map(|hike| match hike.junction {
+                    1 => vec![hike],
+                    _ => self.extend(hike).collect(),
+                })
+                .collect();
+        }
+
+        hikes
+            .par_iter()
+            .filter_map(|&hike| self.longest_from(hike))
+            .max()
+    }
+}
+
+fn parse_input(input: &str) -> Trails {
+    let tiles = parse_all(grid, input).unwrap();
+    let (width, height) = (tiles[0].len(), tiles.len());
+    let opening = |y: usize| Point {
+        x: tiles[y].iter().position(|&c| c == '.').unwrap() as isize,
+        y: y as isize,
+    };
+    Trails {
+        start: opening(0),
+        end: opening(height - 1),
+        tiles,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(94));
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(154));
+    }
+
+    #[test]
+    fn test_junction_graph() {
+        let trails = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(trails.start, Point { x: 1, y: 0 });
+        assert_eq!(trails.end, Point { x: 21, y: 22 });
+
+        let graph = trails.junction_graph(false);
+        assert_eq!(graph.edges.len(), 9);
+        let [(first, length)] = graph.edges[0][..] else {
+            panic!("the start should lead to a single junction");
+        };
+        assert_eq!(length, 15);
+        assert!(graph.edges[first].contains(&(0, 15)));
+
+        // Slopes only lead away from the start, so nothing can get back to it
+        let graph = trails.junction_graph(true);
+        assert!(graph.edges.iter().flatten().all(|&(to, _)| to != 0));
+    }
+}
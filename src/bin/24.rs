@@ -0,0 +1,243 @@
+use advent_of_code::{
+    parse::{lines, parse_all, signed, IResult},
+    Point3,
+};
+use itertools::Itertools;
+use nom::{
+    character::complete::{char, space0},
+    sequence::{delimited, separated_pair, tuple},
+    Parser,
+};
+use nom_supreme::ParserExt;
+use num::{BigInt, BigRational, Signed, Zero};
+
+advent_of_code::solution!(24);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let hailstones = parse_input(input);
+    Some(crossings_within(
+        &hailstones,
+        200_000_000_000_000,
+        400_000_000_000_000,
+    ))
+}
+
+pub fn part_two(input: &str) -> Option<isize> {
+    let hailstones = parse_input(input);
+    let (position, _) = throw(&hailstones)?;
+    Some(position.x + position.y + position.z)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hailstone {
+    position: Point3,
+    velocity: Point3,
+}
+
+fn rational(value: isize) -> BigRational {
+    BigRational::from_integer(BigInt::from(value))
+}
+
+impl Hailstone {
+    /// Where the paths of two hailstones cross in the x/y plane, ignoring z, if they do so in the
+    /// future of both.
+    fn crossing_xy(&self, other: &Hailstone) -> Option<(BigRational, BigRational)> {
+        let (p, v) = (&self.position, &self.velocity);
+        let (q, w) = (&other.position, &other.velocity);
+
+        // Solve p + t * v = q + s * w with Cramer's rule
+        let det = rational(w.x * v.y - v.x * w.y);
+        if det.is_zero() {
+            return None;
+        }
+        let (dx, dy) = (rational(q.x - p.x), rational(q.y - p.y));
+        let t = (&dy * rational(w.x) - &dx * rational(w.y)) / &det;
+        let s = (&dy * rational(v.x) - &dx * rational(v.y)) / &det;
+        if t.is_negative() || s.is_negative() {
+            return None;
+        }
+        Some((
+            rational(p.x) + &t * rational(v.x),
+            rational(p.y) + &t * rational(v.y),
+        ))
+    }
+}
+
+/// Number of pairs of hailstones whose future paths cross inside the square test area.
+fn crossings_within(hailstones: &[Hailstone], min: isize, max: isize) -> usize {
+    let area = rational(min)..=rational(max);
+    hailstones
+        .iter()
+        .tuple_combinations()
+        .filter_map(|(a, b)| a.crossing_xy(b))
+        .filter(|(x, y)| area.contains(x) && area.contains(y))
+        .count()
+}
+
+/// Solves a square system of linear equations, given as rows of coefficients followed by the
+/// constant, with Gaussian elimination. Returns `None` if there's no single solution.
+fn solve(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let n = rows.len();
+    for column in 0..n {
+        let pivot = (column..n).find(|&r| !rows[r][column].is_zero())?;
+        rows.swap(column, pivot);
+
+        let pivot = rows[column].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == column || row[column].is_zero() {
+                continue;
+            }
+            let factor = &row[column] / &pivot[column];
+            for (value, p) in row.iter_mut().zip(&pivot).skip(column) {
+                *value -= &factor * p;
+            }
+        }
+    }
+    Some(
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| &row[n] / &row[i])
+            .collect(),
+    )
+}
+
+/// Rows of the linear system the throw has to satisfy for hailstones `a` and `b`, over the
+/// unknowns `[px, py, pz, vx, vy, vz]`.
+///
+/// The rock hits a hailstone exactly when `(P - p) × (V - v) = 0`. Expanding that leaves a
+/// `P × V` term that is the same for every hailstone, so the difference of two of them is linear:
+/// `P × (vb - va) + (pb - pa) × V = pb × vb - pa × va`.
+fn throw_equations(a: &Hailstone, b: &Hailstone) -> Vec<Vec<BigRational>> {
+    // Positions are too large to cross multiply in 64 bits
+    let wide = |p: &Point3| [p.x as i128, p.y as i128, p.z as i128];
+    let cross = |u: [i128; 3], v: [i128; 3]| {
+        [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ]
+    };
+    let (pa, va) = (wide(&a.position), wide(&a.velocity));
+    let (pb, vb) = (wide(&b.position), wide(&b.velocity));
+    let d: [i128; 3] = std::array::from_fn(|i| vb[i] - va[i]);
+    let e: [i128; 3] = std::array::from_fn(|i| pb[i] - pa[i]);
+    let (cross_b, cross_a) = (cross(pb, vb), cross(pa, va));
+
+    let rows = [
+        [0, d[2], -d[1], 0, -e[2], e[1], cross_b[0] - cross_a[0]],
+        [-d[2], 0, d[0], e[2], 0, -e[0], cross_b[1] - cross_a[1]],
+        [d[1], -d[0], 0, -e[1], e[0], 0, cross_b[2] - cross_a[2]],
+    ];
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&c| BigRational::from_integer(BigInt::from(c)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Position and velocity to throw the rock from so that it hits every hailstone.
+///
+/// Three hailstones pin it down, as long as they give independent equations.
+fn throw(hailstones: &[Hailstone]) -> Option<(Point3, Point3)> {
+    let first = hailstones.first()?;
+    let solution = hailstones[1..]
+        .iter()
+        .tuple_combinations()
+        .find_map(|(b, c)| {
+            let mut rows = throw_equations(first, b);
+            rows.extend(throw_equations(first, c));
+            solve(rows)
+        })?;
+
+    let values: Vec<isize> = solution
+        .iter()
+        .map(|v| v.is_integer().then(|| v.to_integer().try_into().ok())?)
+        .collect::<Option<_>>()?;
+    Some((
+        Point3 {
+            x: values[0],
+            y: values[1],
+            z: values[2],
+        },
+        Point3 {
+            x: values[3],
+            y: values[4],
+            z: values[5],
+        },
+    ))
+}
+
+fn parse_input(input: &str) -> Vec<Hailstone> {
+    parse_all(lines(parse_hailstone), input).unwrap()
+}
+
+fn parse_point(input: &str) -> IResult<'_, Point3> {
+    let separator = || delimited(space0, char(','), space0);
+    tuple((signed, separator(), signed, separator(), signed))
+        .map(|(x, _, y, _, z)| Point3 { x, y, z })
+        .parse(input)
+}
+
+/// Parses a line like `19, 13, 30 @ -2,  1, -2`.
+fn parse_hailstone(input: &str) -> IResult<'_, Hailstone> {
+    separated_pair(
+        parse_point,
+        delimited(space0, char('@'), space0),
+        parse_point,
+    )
+    .map(|(position, velocity)| Hailstone { position, velocity })
+    .context("hailstone")
+    .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossings_within() {
+        let hailstones = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(crossings_within(&hailstones, 7, 27), 2);
+    }
+
+    #[test]
+    fn test_part_two() {
+        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(47));
+    }
+
+    #[test]
+    fn test_throw() {
+        let hailstones = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let (position, velocity) = throw(&hailstones).unwrap();
+        assert_eq!(
+            position,
+            Point3 {
+                x: 24,
+                y: 13,
+                z: 10
+            }
+        );
+        assert_eq!(velocity, Point3 { x: -3, y: 1, z: 2 });
+    }
+
+    #[test]
+    fn test_crossing_xy() {
+        let hailstones = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let (x, y) = hailstones[0].crossing_xy(&hailstones[1]).unwrap();
+        assert_eq!(x, BigRational::new(BigInt::from(43), BigInt::from(3)));
+        assert_eq!(y, BigRational::new(BigInt::from(46), BigInt::from(3)));
+        // Parallel paths never cross
+        assert_eq!(hailstones[1].crossing_xy(&hailstones[2]), None);
+        // Crossed in the past for the first hailstone
+        assert_eq!(hailstones[0].crossing_xy(&hailstones[4]), None);
+    }
+
+    #[test]
+    fn test_parse_hailstone() {
+        let (_, hailstone) = parse_hailstone("20, 19, 15 @  1, -5, -3").unwrap();
+        assert_eq!(hailstone.velocity, Point3 { x: 1, y: -5, z: -3 });
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use advent_of_code::parse::{key_value, lines, parse_all, IResult};
+use nom::{
+    character::complete::{alpha1, space1},
+    multi::separated_list1,
+    Parser,
+};
+use nom_supreme::ParserExt;
+
+advent_of_code::solution!(25, 1);
+
+pub fn part_one(input: &str) -> Option<usize> {
+    let wiring = parse_input(input);
+    let cut = wiring.min_cut()?;
+    Some(cut.side * (wiring.edges.len() - cut.side))
+}
+
+/// Components and the wires between them, each component known by its index.
+#[derive(Debug)]
+struct Wiring {
+    edges: Vec<Vec<usize>>,
+}
+
+/// The fewest wires to cut to split the components in two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cut {
+    wires: usize,
+    /// Number of components on one side of the cut.
+    side: usize,
+}
+
+impl Wiring {
+    fn new(connections: Vec<(&str, Vec<&str>)>) -> Self {
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut edges: Vec<Vec<usize>> = vec![];
+        let mut intern = |name, edges: &mut Vec<Vec<usize>>| {
+            *index.entry(name).or_insert_with(|| {
+                edges.push(vec![]);
+                edges.len() - 1
+            })
+        };
+
+        for (from, to) in connections {
+            let a = intern(from, &mut edges);
+            for name in to {
+                let b = intern(name, &mut edges);
+                edges[a].push(b);
+                edges[b].push(a);
+            }
+        }
+
+        Wiring { edges }
+    }
+
+    /// Global minimum cut with the Stoer-Wagner algorithm.
+    ///
+    /// Each phase grows a set from an arbitrary component by repeatedly adding the most tightly
+    /// connected one. The last component added, `t`, is cut off from everything else by exactly
+    /// the wires joining it to the set, and no cut separating it from the second to last one, `s`,
+    /// is any smaller. So either that's the best cut, or `s` and `t` are on the same side and can
+    /// be merged before the next phase.
+    fn min_cut(&self) -> Option<Cut> {
+        let n = self.edges.len();
+        let mut weights: Vec<HashMap<usize, usize>> = self
+            .edges
+            .iter()
+            .map(|neighbours| {
+                let mut weights = HashMap::new();
+                for &b in neighbours {
+                    *weights.entry(b).or_insert(0) += 1;
+                }
+                weights
+            })
+            .collect();
+        let mut sizes = vec![1; n];
+        let mut active: Vec<usize> = (0..n).collect();
+        let mut best: Option<Cut> = None;
+
+        while active.len() > 1 {
+            let mut added = vec![false; n];
+            let mut connection = vec![0; n];
+            let mut queue = BinaryHeap::from([(0, active[0])]);
+            let mut order = vec![];
+
+            while let Some((weight, a)) = queue.pop() {
+                // Stale entries are left behind whenever a connection grows
+                if added[a] || weight != connection[a] {
+                    continue;
+                }
+                added[a] = true;
+                order.push(a);
+                for (&b, &w) in &weights[a] {
+                    if !added[b] {
+                        connection[b] += w;
+                        queue.push((connection[b], b));
+                    }
+                }
+            }
+
+            let [.., s, t] = order[..] else {
+                // The rest of the graph isn't connected to the first component
+                return Some(Cut {
+                    wires: 0,
+                    side: order.iter().map(|&a| sizes[a]).sum(),
+                });
+            };
+            let cut = Cut {
+                wires: connection[t],
+                side: sizes[t],
+            };
+            if best.is_none_or(|b| cut.wires < b.wires) {
+                best = Some(cut);
+            }
+
+            // Merge t into s
+            for (b, w) in std::mem::take(&mut weights[t]) {
+                weights[b].remove(&t);
+                if b != s {
+                    *weights[s].entry(b).or_insert(0) += w;
+                    *weights[b].entry(s).or_insert(0) += w;
+                }
+            }
+            sizes[s] += sizes[t];
+            active.retain(|&a| a != t);
+        }
+        best
+    }
+}
+
+fn parse_input(input: &str) -> Wiring {
+    Wiring::new(parse_all(lines(parse_component), input).unwrap())
+}
+
+/// Parses a line like `jqt: rhn xhk nvd`.
+fn parse_component(input: &str) -> IResult<'_, (&str, Vec<&str>)> {
+    key_value(alpha1, separated_list1(space1, alpha1))
+        .context("component")
+        .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(54));
+    }
+
+    #[test]
+    fn test_min_cut() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let wiring = parse_input(&input);
+        assert_eq!(wiring.edges.len(), 15);
+        let cut = wiring.min_cut().unwrap();
+        assert_eq!(cut.wires, 3);
+        assert!(cut.side == 6 || cut.side == 9);
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let wiring = parse_input("a: b\nc: d e");
+        assert_eq!(wiring.min_cut().unwrap().wires, 0);
+    }
+}
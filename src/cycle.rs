@@ -0,0 +1,198 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Where an iterated state machine starts repeating itself, and the state it's in after `n` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    /// Number of steps taken before the first state that is part of the cycle.
+    pub prefix: usize,
+    /// Number of steps it takes to get back to the same state once inside the cycle.
+    pub length: usize,
+    /// The state after the requested number of steps.
+    pub state: S,
+}
+
+impl<S> Cycle<S> {
+    /// Maps any step count onto the earliest step count that ends up in the same state.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+}
+
+fn advance<S>(mut state: S, step: &mut impl FnMut(&S) -> S, times: usize) -> S {
+    for _ in 0..times {
+        state = step(&state);
+    }
+    state
+}
+
+/// Brent's cycle detection, only ever keeping two states around.
+pub fn brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> Cycle<S> {
+    // Find the cycle length by teleporting the tortoise to the hare at every power of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Then walk two pointers `length` apart until they meet at the start of the cycle
+    let mut tortoise = initial.clone();
+    let mut hare = advance(initial.clone(), &mut step, length);
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    finish(initial, tortoise, prefix, length, step, n)
+}
+
+/// Floyd's tortoise and hare cycle detection, only ever keeping two states around.
+pub fn floyd<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> Cycle<S> {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut prefix = 0;
+    let mut tortoise = initial.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    finish(initial, tortoise, prefix, length, step, n)
+}
+
+/// Cycle detection that remembers the step at which every state was first seen.
+///
+/// Only the value returned by `key` is stored, so it can be a much smaller summary of the state,
+/// as long as two states with the same key always behave the same from there on.
+pub fn hashed<S: Clone, K: Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+    n: usize,
+) -> Cycle<S> {
+    let mut seen = HashMap::new();
+    let mut at_n = None;
+    let mut state = initial;
+    let mut steps = 0;
+    loop {
+        if steps == n {
+            at_n = Some(state.clone());
+        }
+        if let Some(&prefix) = seen.get(&key(&state)) {
+            let length = steps - prefix;
+            // `state` is the one at step `prefix`, and every `length` steps after it
+            let state = match at_n {
+                Some(state) => state,
+                None => advance(state, &mut step, (n - prefix) % length),
+            };
+            return Cycle {
+                prefix,
+                length,
+                state,
+            };
+        }
+        seen.insert(key(&state), steps);
+        state = step(&state);
+        steps += 1;
+    }
+}
+
+/// Walks forward from the first state of the cycle to wherever step `n` lands.
+fn finish<S>(
+    initial: S,
+    cycle_start: S,
+    prefix: usize,
+    length: usize,
+    mut step: impl FnMut(&S) -> S,
+    n: usize,
+) -> Cycle<S> {
+    let state = if n < prefix {
+        advance(initial, &mut step, n)
+    } else {
+        advance(cycle_start, &mut step, (n - prefix) % length)
+    };
+    Cycle {
+        prefix,
+        length,
+        state,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0, 1, 2, 3, 4, 5, 2, 3, 4, 5, 2, ...
+    fn step(x: &u32) -> u32 {
+        if *x < 5 {
+            x + 1
+        } else {
+            2
+        }
+    }
+
+    #[test]
+    fn test_brent() {
+        let cycle = brent(0, step, 10);
+        assert_eq!((cycle.prefix, cycle.length, cycle.state), (2, 4, 2));
+        assert_eq!(brent(0, step, 1).state, 1);
+    }
+
+    #[test]
+    fn test_floyd() {
+        let cycle = floyd(0, step, 1_000_000_000);
+        assert_eq!((cycle.prefix, cycle.length, cycle.state), (2, 4, 4));
+        assert_eq!(floyd(0, step, 1).state, 1);
+    }
+
+    #[test]
+    fn test_hashed() {
+        let cycle = hashed(0, step, |&x| x, 10);
+        assert_eq!((cycle.prefix, cycle.length, cycle.state), (2, 4, 2));
+        let cycle = hashed(0, step, |&x| x, 1);
+        assert_eq!((cycle.prefix, cycle.length, cycle.state), (2, 4, 1));
+    }
+
+    #[test]
+    fn test_no_prefix() {
+        let cycle = brent(0, |x| (x + 1) % 3, 7);
+        assert_eq!((cycle.prefix, cycle.length, cycle.state), (0, 3, 1));
+        let cycle = hashed(0, |x| (x + 1) % 3, |&x| x, 7);
+        assert_eq!((cycle.prefix, cycle.length, cycle.state), (0, 3, 1));
+    }
+
+    #[test]
+    fn test_equivalent_step() {
+        let cycle = brent(0, step, 0);
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(6), 2);
+        assert_eq!(cycle.equivalent_step(1_000_000_001), 5);
+    }
+}
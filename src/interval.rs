@@ -0,0 +1,262 @@
+use num::PrimInt;
+use std::ops::Range;
+
+/// The overlapping part of two ranges, if there is one.
+pub fn intersect<T: PrimInt>(a: &Range<T>, b: &Range<T>) -> Option<Range<T>> {
+    let start = a.start.max(b.start);
+    let end = a.end.min(b.end);
+    (start < end).then_some(start..end)
+}
+
+/// Splits a range into the part before `at` and the part from `at` onwards, dropping empty sides.
+pub fn split<T: PrimInt>(range: &Range<T>, at: T) -> (Option<Range<T>>, Option<Range<T>>) {
+    let at = at.max(range.start).min(range.end);
+    let before = (range.start < at).then_some(range.start..at);
+    let after = (at < range.end).then_some(at..range.end);
+    (before, after)
+}
+
+/// Moves a range so that `from` lands on `to`, keeping its length.
+///
+/// Works for unsigned types too, as long as the result doesn't go below zero.
+pub fn offset<T: PrimInt>(range: &Range<T>, from: T, to: T) -> Range<T> {
+    if to >= from {
+        range.start + (to - from)..range.end + (to - from)
+    } else {
+        range.start - (from - to)..range.end - (from - to)
+    }
+}
+
+/// A set of values stored as sorted, disjoint, non-adjacent half open ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet { ranges: Vec::new() }
+    }
+
+    /// Sorts and merges the ranges back into canonical form.
+    fn normalize(mut ranges: Vec<Range<T>>) -> Self {
+        ranges.retain(|r| r.start < r.end);
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        RangeSet { ranges: merged }
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Total number of values in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |total, r| total + (r.end - r.start))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end - T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // The ranges are sorted, so find the last one starting at or before the value
+        let idx = self.ranges.partition_point(|r| r.start <= value);
+        idx > 0 && self.ranges[idx - 1].contains(&value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        *self = Self::normalize(ranges);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            result.extend(intersect(a, b));
+            // Whichever ends first can't overlap anything further along
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges: result }
+    }
+
+    pub fn subtract(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = range.start;
+            // Skip removals that end before this range even starts
+            while others.next_if(|o| o.end <= start).is_some() {}
+
+            let mut rest = others.clone();
+            while let Some(o) = rest.next_if(|o| o.start < range.end) {
+                if o.start > start {
+                    result.push(start..o.start);
+                }
+                start = start.max(o.end);
+            }
+            if start < range.end {
+                result.push(start..range.end);
+            }
+        }
+        RangeSet { ranges: result }
+    }
+
+    /// Splits every range at `at`, so that no range crosses it.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (before, after): (Vec<_>, Vec<_>) = self.ranges.iter().map(|r| split(r, at)).unzip();
+        (
+            RangeSet {
+                ranges: before.into_iter().flatten().collect(),
+            },
+            RangeSet {
+                ranges: after.into_iter().flatten().collect(),
+            },
+        )
+    }
+
+    /// Moves every value so that `from` lands on `to`.
+    pub fn offset(&self, from: T, to: T) -> Self {
+        RangeSet {
+            ranges: self.ranges.iter().map(|r| offset(r, from, to)).collect(),
+        }
+    }
+
+    /// Sends the values covered by each `(source, destination start)` mapping to the destination,
+    /// and lets everything no mapping covers through unchanged.
+    ///
+    /// The sources are expected not to overlap each other.
+    pub fn remap(&self, mappings: &[(Range<T>, T)]) -> Self {
+        let mut unmapped = self.clone();
+        let mut mapped = Vec::new();
+        for (source, destination) in mappings {
+            // A zero length mapping covers nothing
+            if source.is_empty() {
+                continue;
+            }
+            let covered = RangeSet::from(source.clone());
+            let hit = unmapped.intersect(&covered);
+            mapped.extend(hit.offset(source.start, *destination).ranges);
+            unmapped = unmapped.subtract(&covered);
+        }
+        mapped.extend(unmapped.ranges);
+        Self::normalize(mapped)
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::normalize(vec![range])
+    }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<u64>]) -> RangeSet<u64> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let result = set(&[5..7, 0..2, 1..3, 3..4, 9..9]);
+        assert_eq!(result.ranges(), &[0..4, 5..7]);
+        assert_eq!(result.len(), 6);
+        assert_eq!(result.min(), Some(0));
+        assert_eq!(result.max(), Some(6));
+    }
+
+    #[test]
+    fn test_range_helpers() {
+        assert_eq!(intersect(&(0..10), &(5..15)), Some(5..10));
+        assert_eq!(intersect(&(0..5), &(5..15)), None);
+        assert_eq!(split(&(0..10), 4), (Some(0..4), Some(4..10)));
+        assert_eq!(split(&(0..10), 12), (Some(0..10), None));
+        assert_eq!(offset(&(50..52), 50, 98), 98..100);
+        assert_eq!(offset(&(98..100), 98, 50), 50..52);
+    }
+
+    #[test]
+    fn test_contains() {
+        let result = set(&[0..4, 10..12]);
+        assert!(result.contains(0));
+        assert!(result.contains(11));
+        assert!(!result.contains(4));
+        assert!(!result.contains(9));
+        assert!(!result.contains(12));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = RangeSet::from(5..25);
+        assert_eq!(a.union(&b), RangeSet::from(0..30));
+        assert_eq!(a.intersect(&b).ranges(), &[5..10, 20..25]);
+        assert_eq!(a.subtract(&b).ranges(), &[0..5, 25..30]);
+        assert_eq!(b.subtract(&a), RangeSet::from(10..20));
+        assert_eq!(
+            a.subtract(&set(&[2..3, 4..6, 28..40])).ranges(),
+            &[0..2, 3..4, 6..10, 20..28]
+        );
+    }
+
+    #[test]
+    fn test_split_at() {
+        let (before, after) = set(&[0..10, 20..30]).split_at(25);
+        assert_eq!(before.ranges(), &[0..10, 20..25]);
+        assert_eq!(after, RangeSet::from(25..30));
+    }
+
+    #[test]
+    fn test_remap() {
+        // The seed-to-soil map from day 5's example
+        let mappings = [(98..100, 50), (50..98, 52)];
+        let result = set(&[79..93, 55..68]).remap(&mappings);
+        assert_eq!(result.ranges(), &[57..70, 81..95]);
+        let result = RangeSet::from(96..101).remap(&mappings);
+        assert_eq!(result.ranges(), &[50..52, 98..101]);
+    }
+
+    #[test]
+    fn test_remap_empty_source() {
+        // A map line with a length of 0
+        let mappings = [(10..10, 0), (20..25, 40)];
+        let result = set(&[5..15, 20..22]).remap(&mappings);
+        assert_eq!(result.ranges(), &[5..15, 40..42]);
+    }
+}
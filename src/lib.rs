@@ -1,9 +1,29 @@
+pub mod cycle;
+pub mod interval;
+pub mod math;
+pub mod nonogram;
+pub mod parse;
+pub mod polygon;
+pub mod search;
+pub mod sequence;
 pub mod template;
 
+use std::sync::OnceLock;
+
 // Use this file to add helper functions and additional modules.
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum Direction {
+/// Whether a flag like `--print` was passed to the solution, e.g. with `cargo solve 18 --print`.
+///
+/// The arguments are only read the first time, so this is cheap to check inside a timed part.
+pub fn flag(name: &str) -> bool {
+    static ARGS: OnceLock<Vec<String>> = OnceLock::new();
+    ARGS.get_or_init(|| std::env::args().skip(1).collect())
+        .iter()
+        .any(|arg| arg == name)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Direction {
     Up,
     Down,
     Left,
@@ -11,7 +31,7 @@ enum Direction {
 }
 
 impl Direction {
-    fn turn_left(&self) -> Direction {
+    pub fn turn_left(&self) -> Direction {
         match self {
             Self::Up => Self::Left,
             Self::Down => Self::Right,
@@ -19,7 +39,7 @@ impl Direction {
             Self::Right => Self::Up,
         }
     }
-    fn turn_right(&self) -> Direction {
+    pub fn turn_right(&self) -> Direction {
         match self {
             Self::Up => Self::Right,
             Self::Down => Self::Left,
@@ -27,9 +47,49 @@ impl Direction {
             Self::Right => Self::Down,
         }
     }
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
 }
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct Point {
-    x: isize,
-    y: isize,
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    /// The point `distance` steps away in the given direction, with up being negative y.
+    pub fn moved(&self, direction: Direction, distance: isize) -> Point {
+        match direction {
+            Direction::Up => Point {
+                x: self.x,
+                y: self.y - distance,
+            },
+            Direction::Down => Point {
+                x: self.x,
+                y: self.y + distance,
+            },
+            Direction::Left => Point {
+                x: self.x - distance,
+                y: self.y,
+            },
+            Direction::Right => Point {
+                x: self.x + distance,
+                y: self.y,
+            },
+        }
+    }
+}
+
+/// A point in space, with z pointing up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
 }
@@ -26,6 +26,7 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            flags: Vec<&'static str>,
         },
         All {
             release: bool,
@@ -71,6 +72,10 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                flags: ["--print", "--trace"]
+                    .into_iter()
+                    .filter(|&flag| args.contains(flag))
+                    .collect(),
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -115,7 +120,8 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                flags,
+            } => solve::handle(day, release, dhat, submit, &flags),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
@@ -0,0 +1,150 @@
+use std::ops::RangeInclusive;
+
+/// Greatest common divisor of `a` and `b`, along with `x` and `y` such that `a * x + b * y = gcd`.
+///
+/// The divisor is never negative.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese remainder theorem.
+///
+/// The moduli don't need to be coprime. Returns the smallest non-negative solution together with
+/// the modulus all solutions repeat at, i.e. the lcm of the moduli, or `None` if the congruences
+/// contradict each other.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |(residue, modulus), &(r, m)| {
+            // residue + modulus * k ≡ r (mod m), solvable only if the gcd divides the difference
+            let (g, inverse, _) = extended_gcd(modulus, m);
+            let difference = r - residue;
+            if difference % g != 0 {
+                return None;
+            }
+            let step = m / g;
+            let k = (difference / g % step * inverse).rem_euclid(step);
+            let lcm = modulus * step;
+            Some(((residue + modulus * k).rem_euclid(lcm), lcm))
+        })
+}
+
+/// Largest integer whose square is at most `n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above converges down onto the floor of the root
+    let mut x = 1 << (n.ilog2() / 2 + 1);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// The integers `x` for which `a * x² + b * x + c < 0`, with `a` positive.
+///
+/// These lie strictly between the roots of the quadratic. The roots come from an integer square
+/// root, then get nudged until the bounds are exact, so no precision is lost to floats.
+pub fn below_zero(a: i128, b: i128, c: i128) -> Option<RangeInclusive<i128>> {
+    assert!(a > 0, "the quadratic has to open upwards");
+    let value = |x: i128| a * x * x + b * x + c;
+
+    let discriminant = b * b - 4 * a * c;
+    if discriminant <= 0 {
+        return None;
+    }
+    let root = isqrt(discriminant as u128) as i128;
+    let mut low = (-b - root).div_euclid(2 * a);
+    let mut high = (-b + root).div_euclid(2 * a);
+
+    while value(low) >= 0 && low <= high {
+        low += 1;
+    }
+    while value(low - 1) < 0 {
+        low -= 1;
+    }
+    while value(high) >= 0 && high >= low {
+        high -= 1;
+    }
+    while value(high + 1) < 0 {
+        high += 1;
+    }
+    (low <= high).then_some(low..=high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        let (g, x, y) = extended_gcd(-12, 18);
+        assert_eq!(g, 6);
+        assert_eq!(-12 * x + 18 * y, 6);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli sharing a factor
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        // Each ghost on day 8 hits Z at multiples of its cycle length
+        assert_eq!(crt(&[(0, 2), (0, 3)]), Some((0, 6)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u64::MAX as u128), u32::MAX as u128);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_below_zero() {
+        // (x - 2)(x - 5) < 0
+        assert_eq!(below_zero(1, -7, 10), Some(3..=4));
+        // Roots at 10 and 20, but the ends don't count
+        assert_eq!(below_zero(1, -30, 200), Some(11..=19));
+        // Touching zero at a single point
+        assert_eq!(below_zero(1, -4, 4), None);
+        assert_eq!(below_zero(1, 0, 1), None);
+        // Roots very close together
+        assert_eq!(below_zero(4, -2, 0), None);
+        assert_eq!(below_zero(1, -71530, 940200), Some(14..=71516));
+    }
+}
@@ -0,0 +1,325 @@
+//! Nonogram lines: rows of cells that are filled in runs of given lengths, in order, with at least
+//! one empty cell between runs. Day 12's springs are one of these, with `#` for filled.
+
+/// A cell of a line, possibly not known yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Filled,
+    Empty,
+    Unknown,
+}
+
+impl Cell {
+    /// Reads `#`, `.` and `?`.
+    pub fn from_char(c: char) -> Option<Cell> {
+        match c {
+            '#' => Some(Cell::Filled),
+            '.' => Some(Cell::Empty),
+            '?' => Some(Cell::Unknown),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::Filled => '#',
+            Cell::Empty => '.',
+            Cell::Unknown => '?',
+        }
+    }
+
+    fn can_empty(self) -> bool {
+        self != Cell::Filled
+    }
+}
+
+/// Repeats a line `times` times with an unknown cell between each copy, along with its runs.
+pub fn unfold(cells: &[Cell], runs: &[usize], times: usize) -> (Vec<Cell>, Vec<usize>) {
+    let cells = vec![cells; times].join(&Cell::Unknown);
+    let runs = runs.repeat(times);
+    (cells, runs)
+}
+
+/// Where each run can go, given which cells are already known.
+struct Placements<'a> {
+    cells: &'a [Cell],
+    runs: &'a [usize],
+    /// Number of known empty cells before each position, to check whether a run fits in O(1).
+    empties: Vec<usize>,
+}
+
+impl<'a> Placements<'a> {
+    fn new(cells: &'a [Cell], runs: &'a [usize]) -> Self {
+        let mut empties = vec![0];
+        for cell in cells {
+            empties.push(empties.last().unwrap() + usize::from(*cell == Cell::Empty));
+        }
+        Placements {
+            cells,
+            runs,
+            empties,
+        }
+    }
+
+    /// Where run `j` ends up, separator included, if it can start at cell `i`.
+    fn place(&self, i: usize, j: usize) -> Option<usize> {
+        let end = i + *self.runs.get(j)?;
+        let n = self.cells.len();
+        if end > n || self.empties[end] != self.empties[i] {
+            return None;
+        }
+        if end == n {
+            Some(n)
+        } else {
+            self.cells[end].can_empty().then_some(end + 1)
+        }
+    }
+
+    /// `table[i][j]` is the number of ways to fit runs `j..` into cells `i..`.
+    fn suffix_counts(&self) -> Vec<Vec<u128>> {
+        let (n, m) = (self.cells.len(), self.runs.len());
+        let mut table = vec![vec![0; m + 1]; n + 1];
+        table[n][m] = 1;
+        for i in (0..n).rev() {
+            for j in 0..=m {
+                let mut ways = 0;
+                if self.cells[i].can_empty() {
+                    ways += table[i + 1][j];
+                }
+                if let Some(next) = self.place(i, j) {
+                    ways += table[next][j + 1];
+                }
+                table[i][j] = ways;
+            }
+        }
+        table
+    }
+
+    /// `table[i][j]` is the number of ways to fit runs `..j` into cells `..i`, leaving cell `i`
+    /// free to start the next run.
+    fn prefix_counts(&self) -> Vec<Vec<u128>> {
+        let (n, m) = (self.cells.len(), self.runs.len());
+        let mut table = vec![vec![0; m + 1]; n + 1];
+        table[0][0] = 1;
+        for i in 0..n {
+            for j in 0..=m {
+                let ways = table[i][j];
+                if ways == 0 {
+                    continue;
+                }
+                if self.cells[i].can_empty() {
+                    table[i + 1][j] += ways;
+                }
+                if let Some(next) = self.place(i, j) {
+                    table[next][j + 1] += ways;
+                }
+            }
+        }
+        table
+    }
+
+    /// Walks down the suffix counts to the `index`th arrangement, placing runs as early as possible
+    /// first.
+    fn nth(&self, suffix: &[Vec<u128>], mut index: u128) -> Option<Vec<Cell>> {
+        if index >= suffix[0][0] {
+            return None;
+        }
+
+        let mut result = Vec::with_capacity(self.cells.len());
+        let (mut i, mut j) = (0, 0);
+        while i < self.cells.len() {
+            if let Some(next) = self.place(i, j) {
+                let ways = suffix[next][j + 1];
+                if index < ways {
+                    result.extend(std::iter::repeat_n(Cell::Filled, self.runs[j]));
+                    if next > i + self.runs[j] {
+                        result.push(Cell::Empty);
+                    }
+                    (i, j) = (next, j + 1);
+                    continue;
+                }
+                index -= ways;
+            }
+            result.push(Cell::Empty);
+            i += 1;
+        }
+        Some(result)
+    }
+}
+
+/// Number of ways to fill in the unknown cells so the line has exactly these runs.
+pub fn count(cells: &[Cell], runs: &[usize]) -> u128 {
+    Placements::new(cells, runs).suffix_counts()[0][0]
+}
+
+/// The cells that are the same in every arrangement, with the rest left unknown.
+///
+/// Returns `None` if the line can't be completed at all.
+pub fn forced(cells: &[Cell], runs: &[usize]) -> Option<Vec<Cell>> {
+    let placements = Placements::new(cells, runs);
+    let suffix = placements.suffix_counts();
+    let total = suffix[0][0];
+    if total == 0 {
+        return None;
+    }
+    let prefix = placements.prefix_counts();
+
+    // Count the arrangements filling each cell, marking runs with a difference array
+    let (n, m) = (cells.len(), runs.len());
+    let mut filled = vec![0i128; n + 1];
+    for i in 0..n {
+        for j in 0..m {
+            if let Some(next) = placements.place(i, j) {
+                let ways = (prefix[i][j] * suffix[next][j + 1]) as i128;
+                filled[i] += ways;
+                filled[i + runs[j]] -= ways;
+            }
+        }
+    }
+
+    let mut running = 0;
+    Some(
+        filled[..n]
+            .iter()
+            .map(|change| {
+                running += change;
+                match running as u128 {
+                    0 => Cell::Empty,
+                    f if f == total => Cell::Filled,
+                    _ => Cell::Unknown,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// The `index`th arrangement, counting in the order `arrangements` yields them.
+///
+/// With a random index below `count` this samples arrangements uniformly.
+pub fn nth_arrangement(cells: &[Cell], runs: &[usize], index: u128) -> Option<Vec<Cell>> {
+    let placements = Placements::new(cells, runs);
+    let suffix = placements.suffix_counts();
+    placements.nth(&suffix, index)
+}
+
+/// Every way to fill in the line, one at a time.
+pub fn arrangements<'a>(
+    cells: &'a [Cell],
+    runs: &'a [usize],
+) -> impl Iterator<Item = Vec<Cell>> + 'a {
+    let placements = Placements::new(cells, runs);
+    let suffix = placements.suffix_counts();
+    (0..suffix[0][0]).map(move |index| placements.nth(&suffix, index).unwrap())
+}
+
+/// A whole nonogram, with the runs for every row and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+impl Puzzle {
+    /// Fills in every cell that follows from looking at one row or column at a time, going back and
+    /// forth until nothing changes.
+    ///
+    /// Cells that need guessing are left unknown. Returns `None` if the runs contradict each other.
+    pub fn solve(&self) -> Option<Vec<Vec<Cell>>> {
+        let (height, width) = (self.rows.len(), self.columns.len());
+        let mut grid = vec![vec![Cell::Unknown; width]; height];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (y, runs) in self.rows.iter().enumerate() {
+                let line = forced(&grid[y], runs)?;
+                changed |= line != grid[y];
+                grid[y] = line;
+            }
+            for (x, runs) in self.columns.iter().enumerate() {
+                let column: Vec<Cell> = grid.iter().map(|row| row[x]).collect();
+                let line = forced(&column, runs)?;
+                if line != column {
+                    changed = true;
+                    for (row, cell) in grid.iter_mut().zip(line) {
+                        row[x] = cell;
+                    }
+                }
+            }
+        }
+        Some(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(s: &str) -> Vec<Cell> {
+        s.chars().map(|c| Cell::from_char(c).unwrap()).collect()
+    }
+
+    fn text(cells: &[Cell]) -> String {
+        cells.iter().map(|c| c.to_char()).collect()
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(count(&line("???.###"), &[1, 1, 3]), 1);
+        assert_eq!(count(&line(".??..??...?##."), &[1, 1, 3]), 4);
+        assert_eq!(count(&line("?###????????"), &[3, 2, 1]), 10);
+        assert_eq!(count(&line("....."), &[]), 1);
+        assert_eq!(count(&line("..#.."), &[]), 0);
+        assert_eq!(count(&line("##"), &[1]), 0);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let (cells, runs) = unfold(&line(".#"), &[1], 3);
+        assert_eq!(text(&cells), ".#?.#?.#");
+        assert_eq!(runs, vec![1, 1, 1]);
+        let (cells, runs) = unfold(&line("?###????????"), &[3, 2, 1], 5);
+        assert_eq!(count(&cells, &runs), 506250);
+    }
+
+    #[test]
+    fn test_forced() {
+        assert_eq!(
+            text(&forced(&line("??????????"), &[8]).unwrap()),
+            "??######??"
+        );
+        assert_eq!(
+            text(&forced(&line("???.###"), &[1, 1, 3]).unwrap()),
+            "#.#.###"
+        );
+        assert_eq!(text(&forced(&line("?#???"), &[2]).unwrap()), "?#?..");
+        assert_eq!(forced(&line("#.#"), &[3]), None);
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let cells = line("?###????????");
+        let all: Vec<String> = arrangements(&cells, &[3, 2, 1]).map(|a| text(&a)).collect();
+        assert_eq!(all.len(), 10);
+        assert_eq!(all[0], ".###.##.#...");
+        assert_eq!(all[9], ".###....##.#");
+        assert!(all.iter().all(|a| count(&line(a), &[3, 2, 1]) == 1));
+        assert_eq!(nth_arrangement(&cells, &[3, 2, 1], 10), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        // A small heart
+        let puzzle = Puzzle {
+            rows: vec![vec![1, 1], vec![5], vec![5], vec![3], vec![1]],
+            columns: vec![vec![2], vec![4], vec![4], vec![4], vec![2]],
+        };
+        let solution: Vec<String> = puzzle.solve().unwrap().iter().map(|r| text(r)).collect();
+        assert_eq!(solution, vec![".#.#.", "#####", "#####", ".###.", "..#.."]);
+
+        let impossible = Puzzle {
+            rows: vec![vec![2], vec![]],
+            columns: vec![vec![], vec![1]],
+        };
+        assert_eq!(impossible.solve(), None);
+    }
+}
@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use nom::{
+    character::complete::{
+        char, digit1, line_ending, multispace0, none_of, one_of, space0, space1,
+    },
+    combinator::{map_opt, opt, recognize},
+    multi::{many1, separated_list1},
+    sequence::{pair, separated_pair, terminated, tuple},
+    Parser,
+};
+use nom_supreme::{
+    error::ErrorTree,
+    final_parser::{final_parser, Location},
+    ParserExt,
+};
+
+/// Result type of every parser in this module, reporting failures as an [`ErrorTree`].
+pub type IResult<'a, O> = nom::IResult<&'a str, O, ErrorTree<&'a str>>;
+
+/// Runs `parser` over the whole input, allowing trailing whitespace such as the final newline.
+///
+/// Errors carry the line and column where parsing went wrong.
+pub fn parse_all<'a, O>(
+    parser: impl Parser<&'a str, O, ErrorTree<&'a str>>,
+    input: &'a str,
+) -> Result<O, ErrorTree<Location>> {
+    final_parser(terminated(parser, multispace0))(input)
+}
+
+/// An unsigned integer, e.g. `42`.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_opt(digit1, |s: &str| s.parse().ok())
+        .context("unsigned integer")
+        .parse(input)
+}
+
+/// An integer with an optional sign, e.g. `-42` or `+7`.
+pub fn signed<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_opt(recognize(pair(opt(one_of("+-")), digit1)), |s: &str| {
+        s.parse().ok()
+    })
+    .context("signed integer")
+    .parse(input)
+}
+
+/// Integers separated by spaces on a single line, e.g. `0 3 -6 9`.
+pub fn numbers<T: FromStr>(input: &str) -> IResult<'_, Vec<T>> {
+    separated_list1(space1, signed)(input)
+}
+
+/// Integers separated by commas, e.g. `1,1,3`.
+pub fn comma_separated<T: FromStr>(input: &str) -> IResult<'_, Vec<T>> {
+    separated_list1(char(','), signed)(input)
+}
+
+/// A `key: value` pair, with any amount of spaces around the colon.
+pub fn key_value<'a, K, V>(
+    key: impl Parser<&'a str, K, ErrorTree<&'a str>>,
+    value: impl Parser<&'a str, V, ErrorTree<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, (K, V)> {
+    separated_pair(key, tuple((space0, char(':'), space0)), value)
+}
+
+/// One or more items, one per line.
+pub fn lines<'a, O>(
+    line: impl Parser<&'a str, O, ErrorTree<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated_list1(line_ending, line)
+}
+
+/// One or more blocks separated by a blank line, like the patterns of day 13.
+pub fn blocks<'a, O>(
+    block: impl Parser<&'a str, O, ErrorTree<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<O>> {
+    separated_list1(pair(line_ending, line_ending), block)
+}
+
+/// A rectangle of characters, one row per line.
+pub fn grid(input: &str) -> IResult<'_, Vec<Vec<char>>> {
+    lines(many1(none_of("\r\n"))).context("grid").parse(input)
+}
+
+/// A rectangle of single digit numbers, like the heat loss map of day 17.
+pub fn digit_grid(input: &str) -> IResult<'_, Vec<Vec<u8>>> {
+    lines(many1(
+        one_of("0123456789").map(|c| c.to_digit(10).unwrap() as u8),
+    ))
+    .context("digit grid")
+    .parse(input)
+}
+
+/// The first of several words that matches, mapped to its value. Mostly useful for enums like directions.
+pub fn word<'a, O: Clone + 'a>(
+    options: &'a [(&'static str, O)],
+) -> impl FnMut(&'a str) -> IResult<'a, O> + 'a {
+    move |input: &'a str| {
+        let mut last_error = None;
+        for (text, value) in options {
+            match nom_supreme::tag::complete::tag(*text).parse(input) {
+                Ok((rest, _)) => return Ok((rest, value.clone())),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            nom::Err::Error(nom::error::ParseError::from_error_kind(
+                input,
+                nom::error::ErrorKind::Alt,
+            ))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom_supreme::tag::complete::tag;
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(unsigned::<u32>("42 rest").unwrap(), (" rest", 42));
+        assert_eq!(signed::<i32>("-42").unwrap(), ("", -42));
+        assert_eq!(signed::<i32>("+7").unwrap(), ("", 7));
+        assert!(unsigned::<u32>("-42").is_err());
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(
+            numbers::<i64>("0 3  -6 9\n1").unwrap(),
+            ("\n1", vec![0, 3, -6, 9])
+        );
+        assert_eq!(
+            comma_separated::<u8>("1,1,3 x").unwrap(),
+            (" x", vec![1, 1, 3])
+        );
+    }
+
+    #[test]
+    fn test_key_value() {
+        let mut game = key_value(tag("Game ").precedes(unsigned::<u32>), numbers::<u32>);
+        assert_eq!(game("Game 12: 3 4").unwrap(), ("", (12, vec![3, 4])));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "#.\n.#\n\n##\n..\n";
+        let result = parse_all(blocks(grid), input).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1], vec![vec!['#', '#'], vec!['.', '.']]);
+    }
+
+    #[test]
+    fn test_digit_grid() {
+        let result = parse_all(digit_grid, "123\n456\n").unwrap();
+        assert_eq!(result, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+
+    #[test]
+    fn test_word() {
+        let mut direction = word(&[("U", 'u'), ("D", 'd')]);
+        assert_eq!(direction("D 6").unwrap(), (" 6", 'd'));
+        assert!(direction("X 6").is_err());
+    }
+
+    #[test]
+    fn test_error_location() {
+        let error = parse_all(lines(numbers::<u32>), "1 2\n3 x\n").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("line 2"), "{message}");
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{Direction, Point};
+use num::integer::gcd;
+
+/// A closed polygon on the integer grid, stored as its corners in drawing order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// Traces a polygon from the origin by following each `(direction, length)` edge in turn.
+    ///
+    /// The edges are expected to lead back to the origin, closing the polygon.
+    pub fn from_edges(edges: impl IntoIterator<Item = (Direction, isize)>) -> Polygon {
+        let mut current = Point { x: 0, y: 0 };
+        let mut vertices = vec![];
+        for (direction, length) in edges {
+            vertices.push(current);
+            current = current.moved(direction, length);
+        }
+        Polygon { vertices }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    /// Twice the enclosed area, via the shoelace formula. Always a whole number on the grid.
+    pub fn double_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+            .sum::<i128>()
+            .abs()
+    }
+
+    pub fn area(&self) -> i128 {
+        self.double_area() / 2
+    }
+
+    /// Number of lattice points lying on the edges.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| gcd((b.x - a.x) as i128, (b.y - a.y) as i128))
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, from Pick's theorem `A = I + B/2 - 1`.
+    pub fn interior_points(&self) -> i128 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the edges, i.e. the cells a dug out trench covers.
+    pub fn enclosed_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: isize) -> Polygon {
+        Polygon::from_edges([
+            (Direction::Right, size),
+            (Direction::Down, size),
+            (Direction::Left, size),
+            (Direction::Up, size),
+        ])
+    }
+
+    #[test]
+    fn test_square() {
+        let polygon = square(2);
+        assert_eq!(polygon.vertices.len(), 4);
+        assert_eq!(polygon.area(), 4);
+        assert_eq!(polygon.boundary_points(), 8);
+        assert_eq!(polygon.interior_points(), 1);
+        assert_eq!(polygon.enclosed_points(), 9);
+    }
+
+    #[test]
+    fn test_winding_direction() {
+        let clockwise = square(5);
+        let anticlockwise = Polygon::from_edges([
+            (Direction::Down, 5),
+            (Direction::Right, 5),
+            (Direction::Up, 5),
+            (Direction::Left, 5),
+        ]);
+        assert_eq!(clockwise.area(), anticlockwise.area());
+        assert_eq!(clockwise.enclosed_points(), 36);
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let polygon = square(1_000_000_000);
+        assert_eq!(polygon.area(), 1_000_000_000_000_000_000);
+        assert_eq!(polygon.enclosed_points(), 1_000_000_001 * 1_000_000_001);
+    }
+
+    #[test]
+    fn test_concave() {
+        // An L shape: a 3x3 block of cells with the top right 2x2 missing
+        let polygon = Polygon::from_edges([
+            (Direction::Right, 1),
+            (Direction::Down, 2),
+            (Direction::Right, 2),
+            (Direction::Down, 1),
+            (Direction::Left, 3),
+            (Direction::Up, 3),
+        ]);
+        assert_eq!(polygon.area(), 5);
+        assert_eq!(polygon.boundary_points(), 12);
+        assert_eq!(polygon.enclosed_points(), 12);
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// The outcome of a successful search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S> {
+    /// Total cost of the path, the number of steps for [`bfs`].
+    pub cost: usize,
+    /// Every state from the start to the goal, both included.
+    pub path: Vec<S>,
+    /// How many states were expanded before the goal was reached.
+    pub visited: usize,
+}
+
+/// Keeps every state seen by a search once, together with its best cost and parent.
+struct Arena<S> {
+    states: Vec<S>,
+    costs: Vec<usize>,
+    parents: Vec<Option<usize>>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash> Arena<S> {
+    fn new() -> Self {
+        Arena {
+            states: Vec::new(),
+            costs: Vec::new(),
+            parents: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Records `state` if it's new or reached more cheaply, returning its index.
+    fn relax(&mut self, state: S, cost: usize, parent: Option<usize>) -> Option<usize> {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => {
+                let idx = *entry.get();
+                if cost >= self.costs[idx] {
+                    return None;
+                }
+                self.costs[idx] = cost;
+                self.parents[idx] = parent;
+                Some(idx)
+            }
+            Entry::Vacant(entry) => {
+                let idx = self.states.len();
+                self.states.push(entry.key().clone());
+                self.costs.push(cost);
+                self.parents.push(parent);
+                entry.insert(idx);
+                Some(idx)
+            }
+        }
+    }
+
+    fn path(&self, mut idx: usize) -> Vec<S> {
+        let mut path = vec![self.states[idx].clone()];
+        while let Some(parent) = self.parents[idx] {
+            path.push(self.states[parent].clone());
+            idx = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn result(&self, idx: usize, visited: usize) -> SearchResult<S> {
+        SearchResult {
+            cost: self.costs[idx],
+            path: self.path(idx),
+            visited,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Frontier {
+    estimate: usize,
+    cost: usize,
+    idx: usize,
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the BinaryHeap pops the cheapest estimate first
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+            .then_with(|| self.idx.cmp(&other.idx))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Breadth first search from every start until `goal` matches, each step costing one.
+pub fn bfs<S, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: FN,
+    mut goal: FG,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut arena = Arena::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        queue.extend(arena.relax(start, 0, None));
+    }
+
+    let mut visited = 0;
+    while let Some(idx) = queue.pop_front() {
+        visited += 1;
+        let state = arena.states[idx].clone();
+        if goal(&state) {
+            return Some(arena.result(idx, visited));
+        }
+
+        let cost = arena.costs[idx] + 1;
+        for next in successors(&state) {
+            if !arena.index.contains_key(&next) {
+                queue.extend(arena.relax(next, cost, Some(idx)));
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm from every start until `goal` matches.
+///
+/// `successors` yields each neighbouring state along with the cost of moving there.
+pub fn dijkstra<S, FN, IN, FG>(
+    starts: impl IntoIterator<Item = S>,
+    successors: FN,
+    goal: FG,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FG: FnMut(&S) -> bool,
+{
+    astar(starts, successors, |_| 0, goal)
+}
+
+/// A* search from every start until `goal` matches.
+///
+/// `heuristic` must never overestimate the remaining cost, otherwise the path found may not be the cheapest.
+pub fn astar<S, FN, IN, FH, FG>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut goal: FG,
+) -> Option<SearchResult<S>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, usize)>,
+    FH: FnMut(&S) -> usize,
+    FG: FnMut(&S) -> bool,
+{
+    let mut arena = Arena::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        let estimate = heuristic(&start);
+        if let Some(idx) = arena.relax(start, 0, None) {
+            heap.push(Frontier {
+                estimate,
+                cost: 0,
+                idx,
+            });
+        }
+    }
+
+    let mut visited = 0;
+    while let Some(Frontier { cost, idx, .. }) = heap.pop() {
+        // Stale entry, the state has been reached more cheaply since
+        if cost > arena.costs[idx] {
+            continue;
+        }
+
+        visited += 1;
+        let state = arena.states[idx].clone();
+        if goal(&state) {
+            return Some(arena.result(idx, visited));
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next_idx) = arena.relax(next, next_cost, Some(idx)) {
+                heap.push(Frontier {
+                    estimate,
+                    cost: next_cost,
+                    idx: next_idx,
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "S.#.....
+.##.###.
+....#...
+.##...#G";
+
+    fn maze() -> Vec<Vec<char>> {
+        MAZE.lines().map(|l| l.chars().collect()).collect()
+    }
+
+    fn neighbours(grid: &[Vec<char>], (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut next = vec![];
+        if x > 0 {
+            next.push((x - 1, y));
+        }
+        if y > 0 {
+            next.push((x, y - 1));
+        }
+        if x + 1 < grid[0].len() {
+            next.push((x + 1, y));
+        }
+        if y + 1 < grid.len() {
+            next.push((x, y + 1));
+        }
+        next.retain(|&(x, y)| grid[y][x] != '#');
+        next
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let result = bfs([(0, 0)], |&p| neighbours(&grid, p), |&p| p == (7, 3)).unwrap();
+        assert_eq!(result.cost, 12);
+        assert_eq!(result.path.len(), 13);
+        assert_eq!(result.path[0], (0, 0));
+        assert_eq!(result.path[12], (7, 3));
+        assert!(result.visited <= 24);
+    }
+
+    #[test]
+    fn test_bfs_unreachable() {
+        let grid = maze();
+        assert_eq!(
+            bfs([(0, 0)], |&p| neighbours(&grid, p), |&p| p == (2, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Taking the long way round is cheaper than the expensive edge
+        let edges = |&n: &u8| match n {
+            0 => vec![(1, 10), (2, 1)],
+            2 => vec![(3, 1)],
+            3 => vec![(1, 1)],
+            1 => vec![(4, 1)],
+            _ => vec![],
+        };
+        let result = dijkstra([0], edges, |&n| n == 4).unwrap();
+        assert_eq!(result.cost, 4);
+        assert_eq!(result.path, vec![0, 2, 3, 1, 4]);
+    }
+
+    #[test]
+    fn test_astar_matches_dijkstra() {
+        let grid = maze();
+        let weighted = |&p: &(usize, usize)| {
+            neighbours(&grid, p)
+                .into_iter()
+                .map(|n| (n, 1 + n.1))
+                .collect::<Vec<_>>()
+        };
+        let goal = |&p: &(usize, usize)| p == (7, 3);
+        let manhattan = |&(x, y): &(usize, usize)| (7 - x) + (3 - y);
+
+        let plain = dijkstra([(0, 0)], weighted, goal).unwrap();
+        let guided = astar([(0, 0)], weighted, manhattan, goal).unwrap();
+        assert_eq!(plain.cost, guided.cost);
+        assert!(guided.visited <= plain.visited);
+    }
+}
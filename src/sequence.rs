@@ -0,0 +1,63 @@
+use num::Num;
+
+/// The last value of each row of repeated differences, down to the first row of all zeros.
+fn last_differences<T: Num + Copy>(values: &[T]) -> Vec<T> {
+    let mut row = values.to_vec();
+    let mut lasts = vec![];
+    while row.iter().any(|x| !x.is_zero()) {
+        lasts.push(*row.last().unwrap());
+        row = row.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    lasts
+}
+
+/// The value following `values`, assuming they come from a polynomial.
+///
+/// Repeatedly takes differences until they're all zero, then adds the last value of each row
+/// back up again.
+pub fn extrapolate<T: Num + Copy>(values: &[T]) -> T {
+    last_differences(values)
+        .into_iter()
+        .fold(T::zero(), |acc, last| acc + last)
+}
+
+/// The value at index `n` of the polynomial sequence starting with `values`.
+///
+/// Steps the difference table forward one value at a time, so it takes `n` steps but never needs
+/// more memory than the table itself. Use a signed `T` unless the differences never go negative.
+pub fn extrapolate_nth<T: Num + Copy>(values: &[T], n: usize) -> T {
+    if n < values.len() {
+        return values[n];
+    }
+    let mut lasts = last_differences(values);
+    for _ in values.len()..=n {
+        for k in (0..lasts.len().saturating_sub(1)).rev() {
+            lasts[k] = lasts[k] + lasts[k + 1];
+        }
+    }
+    lasts.first().copied().unwrap_or_else(T::zero)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extrapolate() {
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12]), 15);
+        assert_eq!(extrapolate(&[1, 3, 6, 10, 15, 21]), 28);
+        assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45]), 68);
+        assert_eq!(extrapolate(&[0, 0, 0]), 0);
+    }
+
+    #[test]
+    fn test_extrapolate_nth() {
+        // Squares, from three samples
+        assert_eq!(extrapolate_nth(&[0, 1, 4], 1), 1);
+        assert_eq!(extrapolate_nth(&[0, 1, 4], 3), 9);
+        assert_eq!(extrapolate_nth(&[0i64, 1, 4], 202300), 202300 * 202300);
+        assert_eq!(extrapolate_nth(&[5, 5], 10), 5);
+        // Decreasing, so the differences are negative
+        assert_eq!(extrapolate_nth(&[10i64, 7, 3], 4), -8);
+    }
+}